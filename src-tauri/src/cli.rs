@@ -0,0 +1,454 @@
+use app_lib::scanner::{
+    ColumnUnit, Confidence, FindingKind, ScanConfig, ScanOptions, Scanner, Script,
+};
+use std::borrow::Cow;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage: chinese-scan [OPTIONS] <PATH>
+
+Scan js/jsx/ts/tsx (and mjs/cjs/mts/cts), vue, svelte, astro, mini-program,
+html and server template (ejs, hbs, pug) files, Java, Go, Python and Kotlin
+sources and extensionless node and python scripts under PATH for Chinese
+text, plus locale resources (json, yaml, properties,
+po, Android strings.xml, iOS strings/stringsdict/xcstrings, Flutter arb)
+outside Chinese locales.
+Files ignored by .gitignore, .ignore or .chinesescanignore are skipped.
+Files that could not be read or parsed are reported as warnings on stderr.
+Settings are also read from chinese-scan.toml or .chinesescanrc in PATH and
+its subdirectories; options given here are added on top.
+Exits with status 1 when anything is found, 2 on error.
+
+Options:
+  -i, --include <GLOB>    Only scan files matching a glob, may be repeated or
+                          comma separated; excludes and .gitignore still apply
+  -e, --exclude <GLOB>    Glob to exclude, may be repeated or comma separated
+  -t, --type <RULE>       Scan files matching a glob or `.ext` as a type, e.g.
+                          `.es6=js` or `legacy/**/*.js=cjs`; may be repeated
+      --skip-dts          Skip .d.ts declaration files
+  -l, --locale <RULE>     Mark files matching a glob as a locale's resources,
+                          e.g. `i18n/*.json=en`; may be repeated
+      --comments          Also scan comments, reported as `comment: TEXT`
+      --identifiers       Also check identifiers, unquoted property keys,
+                          import specifiers and file paths in scripts and
+                          components, reported as e.g. `identifier: NAME`
+      --chinese-locale <LOCALE>
+                          A locale whose files may contain Chinese, e.g. `ja`;
+                          every `zh` locale is one by default
+  -s, --script <NAMES>    Scripts to look for instead of han, comma separated:
+                          han, hiragana, katakana, hangul, non-ascii
+      --pattern <REGEX>   Look for matches of a regex; with no --script, only
+                          for those
+      --split             Report each run of Chinese in a string or text node
+                          as its own finding
+  -f, --format <FORMAT>   Output format: text (default) or json
+      --columns <UNIT>    What columns count: byte (default), char, or utf16
+                          as VS Code does
+      --no-config         Ignore config files
+      --no-gitignore      Also scan files .gitignore and .ignore leave out
+      --skip-hidden       Skip hidden files and directories
+      --follow-links      Follow symbolic links
+      --max-depth <N>     Walk at most N directories deep; 1 scans PATH's
+                          files only
+      --max-size <SIZE>   Skip files larger than SIZE, e.g. 500K or 1M
+  -j, --threads <N>       Worker threads, 0 (default) picks one per CPU
+      --dry-run           List the files that would be scanned, and why each
+                          other file is skipped, without scanning
+  -h, --help              Print this help";
+
+#[derive(Clone, Copy)]
+enum Format {
+    Text,
+    Json,
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, String> {
+        match name {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            other => Err(format!("unknown format: {}", other)),
+        }
+    }
+}
+
+struct Args {
+    path: PathBuf,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    file_types: Vec<String>,
+    skip_declarations: bool,
+    locales: Vec<String>,
+    chinese_locales: Vec<String>,
+    comments: bool,
+    identifiers: bool,
+    split_segments: bool,
+    scripts: Vec<Script>,
+    pattern: Option<String>,
+    format: Option<Format>,
+    column_unit: Option<ColumnUnit>,
+    config: bool,
+    gitignore: bool,
+    skip_hidden: bool,
+    follow_links: bool,
+    max_depth: Option<usize>,
+    max_size: Option<String>,
+    dry_run: bool,
+    threads: usize,
+}
+
+fn parse_args() -> Result<Option<Args>, String> {
+    let mut path = None;
+    let mut include = Vec::new();
+    let mut exclude = Vec::new();
+    let mut file_types = Vec::new();
+    let mut skip_declarations = false;
+    let mut locales = Vec::new();
+    let mut chinese_locales = Vec::new();
+    let mut comments = false;
+    let mut identifiers = false;
+    let mut split_segments = false;
+    let mut scripts = Vec::new();
+    let mut pattern = None;
+    let mut format = None;
+    let mut column_unit = None;
+    let mut config = true;
+    let mut gitignore = true;
+    let mut skip_hidden = false;
+    let mut follow_links = false;
+    let mut max_depth = None;
+    let mut max_size = None;
+    let mut dry_run = false;
+    let mut threads = 0;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-i" | "--include" => {
+                let value = args.next().ok_or("--include requires a value")?;
+                include.extend(
+                    value
+                        .split(',')
+                        .map(|s| s.trim())
+                        .filter(|s| !s.is_empty())
+                        .map(String::from),
+                );
+            }
+            "-e" | "--exclude" => {
+                let value = args.next().ok_or("--exclude requires a value")?;
+                exclude.extend(
+                    value
+                        .split(',')
+                        .map(|s| s.trim())
+                        .filter(|s| !s.is_empty())
+                        .map(String::from),
+                );
+            }
+            "-t" | "--type" => {
+                file_types.push(args.next().ok_or("--type requires a value")?);
+            }
+            "--skip-dts" => skip_declarations = true,
+            "-l" | "--locale" => {
+                locales.push(args.next().ok_or("--locale requires a value")?);
+            }
+            "--chinese-locale" => {
+                chinese_locales.push(args.next().ok_or("--chinese-locale requires a value")?);
+            }
+            "--comments" => comments = true,
+            "--identifiers" => identifiers = true,
+            "--split" => split_segments = true,
+            "-s" | "--script" => {
+                let value = args.next().ok_or("--script requires a value")?;
+                for name in value.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+                    scripts.push(name.parse()?);
+                }
+            }
+            "--pattern" => {
+                pattern = Some(args.next().ok_or("--pattern requires a value")?);
+            }
+            "-f" | "--format" => {
+                format = Some(args.next().ok_or("--format requires a value")?.parse()?);
+            }
+            "--columns" => {
+                column_unit = Some(args.next().ok_or("--columns requires a value")?.parse()?);
+            }
+            "--no-config" => config = false,
+            "--no-gitignore" => gitignore = false,
+            "--skip-hidden" => skip_hidden = true,
+            "--follow-links" => follow_links = true,
+            "--max-depth" => {
+                let value = args.next().ok_or("--max-depth requires a value")?;
+                max_depth = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid depth: {}", value))?,
+                );
+            }
+            "--max-size" => {
+                max_size = Some(args.next().ok_or("--max-size requires a value")?);
+            }
+            "--dry-run" => dry_run = true,
+            "-j" | "--threads" => {
+                let value = args.next().ok_or("--threads requires a value")?;
+                threads = value
+                    .parse()
+                    .map_err(|_| format!("invalid thread count: {}", value))?;
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option: {}", arg)),
+            _ if path.is_none() => path = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument: {}", arg)),
+        }
+    }
+
+    let path = path.ok_or("missing <PATH>")?;
+    Ok(Some(Args {
+        path,
+        include,
+        exclude,
+        file_types,
+        skip_declarations,
+        locales,
+        chinese_locales,
+        comments,
+        identifiers,
+        split_segments,
+        scripts,
+        pattern,
+        format,
+        column_unit,
+        config,
+        gitignore,
+        skip_hidden,
+        follow_links,
+        max_depth,
+        max_size,
+        dry_run,
+        threads,
+    }))
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    // The output format is the one setting only the command line reads.
+    let format = match args.format {
+        Some(format) => format,
+        None if args.config => match ScanConfig::find(&args.path) {
+            Ok(Some((
+                _,
+                ScanConfig {
+                    format: Some(name), ..
+                },
+            ))) => match name.parse() {
+                Ok(format) => format,
+                Err(e) => {
+                    eprintln!("error: {}", e);
+                    return ExitCode::from(2);
+                }
+            },
+            _ => Format::Text,
+        },
+        None => Format::Text,
+    };
+
+    let mut options = ScanOptions::new(args.path)
+        .includes(args.include)
+        .excludes(args.exclude)
+        .skip_declarations(args.skip_declarations)
+        .comments(args.comments)
+        .identifiers(args.identifiers)
+        .split_segments(args.split_segments)
+        .config(args.config)
+        .skip_hidden(args.skip_hidden)
+        .follow_links(args.follow_links)
+        .threads(args.threads);
+    // Left unset so a config file can turn it off.
+    if !args.gitignore {
+        options = options.gitignore(false);
+    }
+    if let Some(depth) = args.max_depth {
+        options = options.max_depth(depth);
+    }
+    if let Some(unit) = args.column_unit {
+        options = options.column_unit(unit);
+    }
+    if !args.scripts.is_empty() || args.pattern.is_some() {
+        options = options.scripts(args.scripts);
+    }
+    if let Some(pattern) = args.pattern {
+        options = options.pattern(pattern);
+    }
+    for locale in args.chinese_locales {
+        options = options.chinese_locale(locale);
+    }
+    let options = args
+        .max_size
+        .iter()
+        .try_fold(options, |options, size| options.max_file_size_text(size))
+        .and_then(|options| {
+            args.file_types
+                .iter()
+                .try_fold(options, |options, rule| options.file_type_rule(rule))
+        })
+        .and_then(|options| {
+            args.locales
+                .iter()
+                .try_fold(options, |options, rule| options.locale_rule(rule))
+        });
+    let options = match options {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    if args.dry_run {
+        return dry_run(&options, format);
+    }
+    let report = match Scanner::scan(&options) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+
+    match format {
+        Format::Text => {
+            for result in &report.results {
+                let marker = match result.confidence {
+                    Confidence::High => "",
+                    Confidence::Low => " (low confidence)",
+                };
+                let key = match &result.key {
+                    Some(key) => format!("{} = ", escape_control(key)),
+                    None => String::new(),
+                };
+                let kind = match result.kind {
+                    FindingKind::Comment => "comment: ",
+                    FindingKind::Identifier => "identifier: ",
+                    FindingKind::PropertyKey => "property key: ",
+                    FindingKind::ImportSpecifier => "import: ",
+                    FindingKind::FilePath => "file path: ",
+                    _ => "",
+                };
+                println!(
+                    "{}:{}:{}: {}{}{}{}",
+                    result.file_path,
+                    result.line,
+                    result.column,
+                    kind,
+                    key,
+                    escape_control(&result.text),
+                    marker
+                );
+            }
+            for config in &report.configs {
+                eprintln!("{}: using config", config);
+            }
+            for diagnostic in &report.diagnostics {
+                match (diagnostic.line, diagnostic.column) {
+                    (Some(line), Some(column)) => eprintln!(
+                        "{}:{}:{}: warning: {}",
+                        diagnostic.file_path, line, column, diagnostic.message
+                    ),
+                    _ => eprintln!("{}: warning: {}", diagnostic.file_path, diagnostic.message),
+                }
+            }
+        }
+        Format::Json => match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::from(2);
+            }
+        },
+    }
+
+    if report.results.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    }
+}
+
+/// Escapes line breaks, tabs and other control characters in found text, so
+/// each finding stays on one line of output.
+fn escape_control(text: &str) -> Cow<'_, str> {
+    if !text.chars().any(char::is_control) {
+        return Cow::Borrowed(text);
+    }
+    let mut escaped = String::with_capacity(text.len() + 8);
+    for c in text.chars() {
+        match c {
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+fn dry_run(options: &ScanOptions, format: Format) -> ExitCode {
+    let report = match Scanner::dry_run(options) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+    match format {
+        Format::Text => {
+            for file in &report.files {
+                let slash = if file.is_dir { "/" } else { "" };
+                match (file.skipped, file.file_type) {
+                    (Some(reason), _) => {
+                        println!(
+                            "{}{}: skip, {}",
+                            file.file_path,
+                            slash,
+                            reason.description()
+                        )
+                    }
+                    (None, Some(file_type)) => match &file.locale {
+                        Some(locale) => {
+                            println!("{}: scan as {} for {}", file.file_path, file_type, locale)
+                        }
+                        None => println!("{}: scan as {}", file.file_path, file_type),
+                    },
+                    (None, None) => {}
+                }
+            }
+            for config in &report.configs {
+                eprintln!("{}: using config", config);
+            }
+            for diagnostic in &report.diagnostics {
+                eprintln!("{}: warning: {}", diagnostic.file_path, diagnostic.message);
+            }
+        }
+        Format::Json => match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::from(2);
+            }
+        },
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,184 @@
+use crate::scanner::{
+    DryRunReport, ScanObserver, ScanOptions, ScanProgress, ScanReport, ScanResult, Scanner,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+
+/// Minimum time between two `scan-progress` events.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Cancellation flags of the scans currently running, keyed by scan ID.
+#[derive(Default)]
+pub struct RunningScans(Mutex<HashMap<String, Arc<AtomicBool>>>);
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ProgressPayload<'a> {
+    scan_id: &'a str,
+    #[serde(flatten)]
+    progress: &'a ScanProgress,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct FileResultsPayload<'a> {
+    scan_id: &'a str,
+    file_path: &'a str,
+    results: &'a [ScanResult],
+}
+
+/// Forwards scan updates to the window as `scan-progress` and `scan-results` events.
+struct EventObserver {
+    app: AppHandle,
+    scan_id: String,
+    cancelled: Arc<AtomicBool>,
+    last_progress: Mutex<Option<Instant>>,
+}
+
+impl ScanObserver for EventObserver {
+    fn on_progress(&self, progress: &ScanProgress) {
+        let mut last_progress = self.last_progress.lock().unwrap();
+        if last_progress.is_some_and(|last| last.elapsed() < PROGRESS_INTERVAL) {
+            return;
+        }
+        *last_progress = Some(Instant::now());
+        let _ = self.app.emit(
+            "scan-progress",
+            ProgressPayload {
+                scan_id: &self.scan_id,
+                progress,
+            },
+        );
+    }
+
+    fn on_file_results(&self, file_path: &str, results: &[ScanResult]) {
+        let _ = self.app.emit(
+            "scan-results",
+            FileResultsPayload {
+                scan_id: &self.scan_id,
+                file_path,
+                results,
+            },
+        );
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// The scan settings as entered in the window; lists are comma separated.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanSettings {
+    path: String,
+    include: String,
+    exclude: String,
+    file_types: String,
+    skip_declarations: bool,
+    locales: String,
+    comments: bool,
+    identifiers: bool,
+    scripts: String,
+    pattern: String,
+    gitignore: bool,
+    skip_hidden: bool,
+    follow_links: bool,
+    max_depth: Option<usize>,
+    max_file_size: String,
+}
+
+impl ScanSettings {
+    fn options(&self) -> Result<ScanOptions, String> {
+        let mut options = ScanOptions::new(&self.path)
+            .include_list(&self.include)
+            .exclude_list(&self.exclude)
+            .file_type_list(&self.file_types)?
+            .skip_declarations(self.skip_declarations)
+            .locale_list(&self.locales)?
+            .comments(self.comments)
+            .identifiers(self.identifiers)
+            .script_list(&self.scripts)?
+            .skip_hidden(self.skip_hidden)
+            .follow_links(self.follow_links);
+        // Left unset when checked so a config file can still turn it off.
+        if !self.gitignore {
+            options = options.gitignore(false);
+        }
+        if let Some(depth) = self.max_depth {
+            options = options.max_depth(depth);
+        }
+        let max_file_size = self.max_file_size.trim();
+        if !max_file_size.is_empty() {
+            options = options.max_file_size_text(max_file_size)?;
+        }
+        let pattern = self.pattern.trim();
+        if !pattern.is_empty() {
+            // With a pattern alone, look for nothing else.
+            if self.scripts.trim().is_empty() {
+                options = options.scripts([]);
+            }
+            options = options.pattern(pattern);
+        }
+        Ok(options)
+    }
+}
+
+#[tauri::command]
+pub async fn scan_directory(
+    app: AppHandle,
+    running: State<'_, RunningScans>,
+    scan_id: String,
+    settings: ScanSettings,
+) -> Result<ScanReport, String> {
+    let cancelled = Arc::new(AtomicBool::new(false));
+    {
+        let mut scans = running.0.lock().unwrap();
+        if scans.contains_key(&scan_id) {
+            return Err(format!("Scan {} is already running", scan_id));
+        }
+        scans.insert(scan_id.clone(), Arc::clone(&cancelled));
+    }
+
+    // Run on a blocking thread so events reach the window while the scan runs.
+    let id = scan_id.clone();
+    let report = tauri::async_runtime::spawn_blocking(move || {
+        let options = settings.options()?;
+        let observer = EventObserver {
+            app,
+            scan_id: id,
+            cancelled,
+            last_progress: Mutex::new(None),
+        };
+        Scanner::scan_with(&options, &observer)
+    })
+    .await;
+
+    running.0.lock().unwrap().remove(&scan_id);
+    report.map_err(|e| e.to_string())?
+}
+
+/// Lists the files a scan with `settings` would read, and why the others
+/// would be skipped.
+#[tauri::command]
+pub async fn dry_run(settings: ScanSettings) -> Result<DryRunReport, String> {
+    tauri::async_runtime::spawn_blocking(move || Scanner::dry_run(&settings.options()?))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Asks a running scan to stop. It returns its partial results marked as cancelled.
+#[tauri::command]
+pub fn cancel_scan(running: State<'_, RunningScans>, scan_id: String) -> Result<(), String> {
+    match running.0.lock().unwrap().get(&scan_id) {
+        Some(cancelled) => {
+            cancelled.store(true, Ordering::Relaxed);
+            Ok(())
+        }
+        None => Err(format!("No running scan with id {}", scan_id)),
+    }
+}
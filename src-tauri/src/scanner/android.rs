@@ -0,0 +1,122 @@
+//! Android string resources: `res/values*/strings.xml` and the other XML
+//! files in those directories.
+//!
+//! `<string>` values are reported under their `name`, `<string-array>` items
+//! as `name[0]` and `<plurals>` items as `name.one`. Inline markup such as
+//! `<b>` or `<xliff:g>` is dropped from the reported value, and CDATA,
+//! entities and Android's backslash escapes and quoting are decoded.
+
+use super::file::{FileContext, FileScan};
+use super::markup::{attribute_value, decode_entities, Token, Tokenizer};
+
+pub(crate) fn scan_strings(ctx: &FileContext) -> FileScan {
+    let source = ctx.source_text;
+    let mut out = FileScan::default();
+    let mut tokenizer = Tokenizer::new(source, 0, source.len());
+    // The open `<string-array>` or `<plurals>`: its name, whether it is a
+    // plurals element, and the index of its next item.
+    let mut array: Option<(String, bool, usize)> = None;
+
+    while let Some(token) = tokenizer.next() {
+        match token {
+            Token::StartTag {
+                name: "string",
+                attributes,
+                self_closing: false,
+            } => {
+                let key = attribute_value(&attributes, "name").map_or("", |value| value.text);
+                let (raw, start) = tokenizer.raw_text("string");
+                ctx.check_value(&decode_value(raw), raw, start, key, &mut out);
+            }
+            Token::StartTag {
+                name: name @ ("string-array" | "plurals"),
+                attributes,
+                self_closing: false,
+            } => {
+                let key = attribute_value(&attributes, "name").map_or("", |value| value.text);
+                array = Some((key.to_string(), name == "plurals", 0));
+            }
+            Token::StartTag {
+                name: "item",
+                attributes,
+                self_closing: false,
+            } => {
+                let Some((array_name, plurals, index)) = &mut array else {
+                    continue;
+                };
+                let key = match attribute_value(&attributes, "quantity") {
+                    Some(quantity) if *plurals => format!("{}.{}", array_name, quantity.text),
+                    _ => format!("{}[{}]", array_name, index),
+                };
+                *index += 1;
+                let (raw, start) = tokenizer.raw_text("item");
+                ctx.check_value(&decode_value(raw), raw, start, &key, &mut out);
+            }
+            Token::EndTag {
+                name: "string-array" | "plurals",
+            } => array = None,
+            _ => {}
+        }
+    }
+    out
+}
+
+/// The text of a resource value as the app shows it.
+fn decode_value(raw: &str) -> String {
+    // Markup and entities first; CDATA content is taken as written.
+    let mut text = String::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(lt) = rest.find('<') {
+        text.push_str(&decode_entities(&rest[..lt]));
+        rest = &rest[lt..];
+        if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let end = cdata.find("]]>").unwrap_or(cdata.len());
+            text.push_str(&cdata[..end]);
+            rest = cdata.get(end + 3..).unwrap_or("");
+        } else {
+            rest = rest.find('>').map_or("", |gt| &rest[gt + 1..]);
+        }
+    }
+    text.push_str(&decode_entities(rest));
+
+    // Then backslash escapes; unescaped double quotes only preserve spacing.
+    let mut value = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {}
+            '\\' => match chars.next() {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some('u') => {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    let decoded = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+                    value.push(decoded.unwrap_or('\u{fffd}'));
+                }
+                Some(other) => value.push(other),
+                None => {}
+            },
+            c => value.push(c),
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::scan_strings;
+    use crate::scanner::file::{covered, keyed, scan_test_source};
+
+    #[test]
+    fn strings_arrays_and_plurals() {
+        let source = "<resources>\n    <string name=\"save\">保存</string>\n    <string name=\"bold\">点击<b>这里</b></string>\n    <string-array name=\"days\">\n        <item>周一</item>\n    </string-array>\n    <plurals name=\"items\">\n        <item quantity=\"one\">一项</item>\n    </plurals>\n</resources>\n";
+        let results = scan_test_source(source, scan_strings);
+        assert_eq!(results.len(), 4);
+        assert_eq!(covered(source, keyed(&results, "save")), "保存");
+        let bold = keyed(&results, "bold");
+        assert_eq!(bold.text, "点击这里");
+        assert_eq!(covered(source, bold), "点击<b>这里");
+        assert_eq!(covered(source, keyed(&results, "days[0]")), "周一");
+        assert_eq!(covered(source, keyed(&results, "items.one")), "一项");
+    }
+}
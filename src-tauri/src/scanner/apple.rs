@@ -0,0 +1,265 @@
+//! Apple localization files: `.strings` tables, `.stringsdict` plural rules
+//! and `.xcstrings` string catalogs.
+//!
+//! `.strings` and `.stringsdict` files live in `en.lproj`-style folders, which
+//! give their locale. A string catalog holds every locale in one file, so each
+//! of its findings carries the locale of the localization it was found in.
+
+use super::file::{FileContext, FileScan};
+use super::json;
+use super::key_path::KeyPath;
+use super::markup::{decode_entities, Token, Tokenizer};
+use super::DiagnosticKind;
+
+/// Scans a `.strings` table of `"key" = "value";` pairs.
+pub(crate) fn scan_strings(ctx: &FileContext) -> FileScan {
+    let mut out = FileScan::default();
+    if let Err((message, offset)) = read_strings(ctx, &mut out) {
+        out.diagnostics
+            .push(ctx.diagnostic(DiagnosticKind::Parse, message, Some(offset as u32)));
+    }
+    out
+}
+
+fn read_strings(ctx: &FileContext, out: &mut FileScan) -> Result<(), (String, usize)> {
+    let source = ctx.source_text;
+    let bytes = source.as_bytes();
+    let mut pos = if source.starts_with('\u{feff}') { 3 } else { 0 };
+    loop {
+        pos = skip_trivia(source, pos);
+        if pos >= bytes.len() {
+            return Ok(());
+        }
+        let key = literal(source, pos)?;
+        pos = skip_trivia(source, key.end);
+        // `"key";` is short for `"key" = "key";`.
+        if bytes.get(pos) == Some(&b';') {
+            pos += 1;
+            continue;
+        }
+        if bytes.get(pos) != Some(&b'=') {
+            return Err(("Expected '='".to_string(), pos));
+        }
+        pos = skip_trivia(source, pos + 1);
+        let value = literal(source, pos)?;
+        ctx.check_value(&value.value, value.raw, value.start, &key.value, out);
+        pos = skip_trivia(source, value.end);
+        if bytes.get(pos) != Some(&b';') {
+            return Err(("Expected ';'".to_string(), pos));
+        }
+        pos += 1;
+    }
+}
+
+/// A key or value in a `.strings` table.
+struct Literal<'s> {
+    /// The value with escapes decoded.
+    value: String,
+    /// Byte offset of the raw content, just after the opening quote if any.
+    start: usize,
+    raw: &'s str,
+    /// Byte offset just past the literal.
+    end: usize,
+}
+
+/// Reads a quoted string or an unquoted word at `pos`.
+fn literal(source: &str, pos: usize) -> Result<Literal<'_>, (String, usize)> {
+    let bytes = source.as_bytes();
+    if bytes.get(pos) != Some(&b'"') {
+        let mut end = pos;
+        while end < bytes.len()
+            && !bytes[end].is_ascii_whitespace()
+            && !matches!(bytes[end], b'=' | b';' | b'"')
+        {
+            end += 1;
+        }
+        if end == pos {
+            return Err(("Expected a string".to_string(), pos));
+        }
+        return Ok(Literal {
+            value: source[pos..end].to_string(),
+            start: pos,
+            raw: &source[pos..end],
+            end,
+        });
+    }
+
+    let start = pos + 1;
+    let mut value = String::new();
+    let mut chars = source[start..].char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => {
+                return Ok(Literal {
+                    value,
+                    start,
+                    raw: &source[start..start + i],
+                    end: start + i + 1,
+                })
+            }
+            '\\' => match chars.next().map(|(_, escape)| escape) {
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some('r') => value.push('\r'),
+                Some('u' | 'U') => {
+                    let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                    let decoded = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+                    value.push(decoded.unwrap_or('\u{fffd}'));
+                }
+                Some(other) => value.push(other),
+                None => break,
+            },
+            c => value.push(c),
+        }
+    }
+    Err(("Unterminated string".to_string(), pos))
+}
+
+/// Skips whitespace and `/* */` and `//` comments.
+fn skip_trivia(source: &str, mut pos: usize) -> usize {
+    let bytes = source.as_bytes();
+    while pos < bytes.len() {
+        if bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        } else if source[pos..].starts_with("/*") {
+            pos = source[pos + 2..]
+                .find("*/")
+                .map_or(bytes.len(), |p| pos + 2 + p + 2);
+        } else if source[pos..].starts_with("//") {
+            pos = source[pos..].find('\n').map_or(bytes.len(), |p| pos + p);
+        } else {
+            break;
+        }
+    }
+    pos
+}
+
+/// Scans a `.stringsdict` property list. Values are reported under their
+/// dictionary keys, e.g. `items_count.items.one`.
+pub(crate) fn scan_stringsdict(ctx: &FileContext) -> FileScan {
+    let source = ctx.source_text;
+    let mut out = FileScan::default();
+    let mut tokenizer = Tokenizer::new(source, 0, source.len());
+    let mut path = KeyPath::default();
+    // The open `<dict>` and `<array>` elements: the path length inside them
+    // and, for arrays, the index of the next value.
+    let mut containers: Vec<(usize, Option<usize>)> = Vec::new();
+
+    while let Some(token) = tokenizer.next() {
+        match token {
+            Token::StartTag {
+                name: "key",
+                self_closing: false,
+                ..
+            } => {
+                let (raw, _) = tokenizer.raw_text("key");
+                if let Some(&(len, None)) = containers.last() {
+                    path.truncate(len);
+                    path.push_key(decode_entities(raw));
+                }
+            }
+            Token::StartTag {
+                name, self_closing, ..
+            } if name != "plist" => {
+                // Every other element is a value: in a dictionary it belongs
+                // to the last key, in an array it takes the next index.
+                if let Some((len, Some(index))) = containers.last_mut() {
+                    path.truncate(*len);
+                    path.push_index(*index);
+                    *index += 1;
+                }
+                match name {
+                    _ if self_closing => {}
+                    "string" => {
+                        let (raw, start) = tokenizer.raw_text("string");
+                        let value = decode_entities(raw);
+                        if ctx.chinese_regex.is_match(&value) {
+                            ctx.check_value(&value, raw, start, &path.to_string(), &mut out);
+                        }
+                    }
+                    "dict" => containers.push((path.len(), None)),
+                    "array" => containers.push((path.len(), Some(0))),
+                    _ => {}
+                }
+            }
+            Token::EndTag {
+                name: "dict" | "array",
+            } => {
+                if let Some((len, _)) = containers.pop() {
+                    path.truncate(len);
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Scans an `.xcstrings` string catalog. Each translated value is reported
+/// under its string's key, followed by its variation for plurals and device
+/// variants (`%lld items.plural.one`), with its localization's locale.
+pub(crate) fn scan_catalog(ctx: &FileContext) -> FileScan {
+    let mut out = FileScan::default();
+    let result = json::walk_strings(ctx.source_text, |string| {
+        // strings.<key>.localizations.<locale>[.variations...].stringUnit.value
+        let path = string.path;
+        let len = path.len();
+        if len < 6
+            || path.key(0) != Some("strings")
+            || path.key(2) != Some("localizations")
+            || path.key(len - 2) != Some("stringUnit")
+            || path.key(len - 1) != Some("value")
+        {
+            return;
+        }
+        let (Some(name), Some(locale)) = (path.key(1), path.key(3)) else {
+            return;
+        };
+        let mut key = name.to_string();
+        for segment in (4..len - 2).filter_map(|i| path.key(i)) {
+            if segment != "variations" {
+                key.push('.');
+                key.push_str(segment);
+            }
+        }
+
+        let found = out.results.len();
+        ctx.check_value(&string.value, string.raw, string.start, &key, &mut out);
+        if let Some(result) = out.results.get_mut(found) {
+            result.locale = Some(locale.to_string());
+        }
+    });
+    if let Err((message, offset)) = result {
+        out.diagnostics
+            .push(ctx.diagnostic(DiagnosticKind::Parse, message, Some(offset as u32)));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{scan_strings, scan_stringsdict};
+    use crate::scanner::file::{covered, keyed, scan_test_source};
+
+    #[test]
+    fn strings_table() {
+        let source = "/* 注释 */\n\"save\" = \"保存\";\n\"escaped\" = \"\\U4E2D文\";\n";
+        let results = scan_test_source(source, scan_strings);
+        assert_eq!(results.len(), 2);
+        assert_eq!(covered(source, keyed(&results, "save")), "保存");
+        let escaped = keyed(&results, "escaped");
+        assert_eq!(escaped.text, "中文");
+        assert_eq!(covered(source, escaped), "\\U4E2D文");
+    }
+
+    #[test]
+    fn stringsdict_key_paths() {
+        let source = "<plist version=\"1.0\">\n<dict>\n  <key>items_count</key>\n  <dict>\n    <key>NSStringLocalizedFormatKey</key>\n    <string>%#@items@</string>\n    <key>items</key>\n    <dict>\n      <key>one</key>\n      <string>一项</string>\n    </dict>\n  </dict>\n</dict>\n</plist>\n";
+        let results = scan_test_source(source, scan_stringsdict);
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            covered(source, keyed(&results, "items_count.items.one")),
+            "一项"
+        );
+    }
+}
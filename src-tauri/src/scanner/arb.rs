@@ -0,0 +1,27 @@
+//! Flutter `.arb` (Application Resource Bundle) files.
+//!
+//! These are JSON objects of messages. `@key` entries hold a message's
+//! description and placeholders for translators, and `@@locale` and the like
+//! are file attributes, so only the other top-level values are reported.
+//! ICU messages such as `{count, plural, one{...}}` are reported whole.
+
+use super::file::{FileContext, FileScan};
+use super::json;
+use super::DiagnosticKind;
+
+pub(crate) fn scan_arb(ctx: &FileContext) -> FileScan {
+    let mut out = FileScan::default();
+    let result = json::walk_strings(ctx.source_text, |string| {
+        let Some(key) = string.path.key(0) else {
+            return;
+        };
+        if string.path.len() == 1 && !key.starts_with('@') {
+            ctx.check_value(&string.value, string.raw, string.start, key, &mut out);
+        }
+    });
+    if let Err((message, offset)) = result {
+        out.diagnostics
+            .push(ctx.diagnostic(DiagnosticKind::Parse, message, Some(offset as u32)));
+    }
+    out
+}
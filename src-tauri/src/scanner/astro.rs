@@ -0,0 +1,95 @@
+//! Astro components. The `---` frontmatter is TypeScript; the markup after it
+//! is scanned with `{...}` expressions parsed as TSX, so JSX inside them such
+//! as `{items.map((item) => <li>{item}</li>)}` is covered too.
+
+use super::component::{scan_markup, Dialect};
+use super::file::{FileContext, FileScan};
+use super::js;
+use oxc::allocator::Allocator;
+use oxc::span::SourceType;
+
+pub(crate) fn scan_astro(ctx: &FileContext, allocator: &Allocator) -> FileScan {
+    let source = ctx.source_text;
+    let typescript = SourceType::default()
+        .with_module(true)
+        .with_typescript(true);
+    let dialect = Dialect {
+        delimiters: ("{", "}"),
+        script_tags: &["script"],
+        default_script: typescript,
+        expression: typescript.with_jsx(true),
+        logic_blocks: false,
+        interpolate_quoted: false,
+    };
+
+    let mut out = FileScan::default();
+    let mut markup_start = 0;
+    if let Some((start, end, after)) = frontmatter(source) {
+        out.extend(js::scan_script(
+            ctx,
+            allocator,
+            &source[start..end],
+            start as u32,
+            typescript,
+        ));
+        markup_start = after;
+    }
+    scan_markup(
+        ctx,
+        allocator,
+        markup_start,
+        source.len(),
+        &dialect,
+        &mut out,
+    );
+    out
+}
+
+/// Finds the frontmatter fenced by `---` lines at the top of the file. Returns
+/// the code's start and end and where the markup begins.
+fn frontmatter(source: &str) -> Option<(usize, usize, usize)> {
+    let lead = source.len() - source.trim_start().len();
+    let rest = source[lead..].strip_prefix("---")?;
+    let start = lead + 3;
+    let mut line_start = start + rest.find('\n')? + 1;
+    loop {
+        let line_end = source[line_start..]
+            .find('\n')
+            .map_or(source.len(), |p| line_start + p);
+        if source[line_start..line_end].trim_end() == "---" {
+            return Some((start, line_start, line_end));
+        }
+        if line_end == source.len() {
+            return None;
+        }
+        line_start = line_end + 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::scan_astro;
+    use crate::scanner::file::{covered, scan_test_source};
+    use crate::scanner::FindingKind;
+    use oxc::allocator::Allocator;
+
+    #[test]
+    fn frontmatter_and_template() {
+        let source = "---\nconst title = \"页面\";\n---\n<Layout title=\"布局\">\n  <h1>欢迎 {title}</h1>\n  {list.map((item) => <li>{item}项</li>)}\n</Layout>\n";
+        let allocator = Allocator::default();
+        let results = scan_test_source(source, |ctx| scan_astro(ctx, &allocator));
+        let found: Vec<_> = results
+            .iter()
+            .map(|result| (covered(source, result), result.kind))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("页面", FindingKind::StringLiteral),
+                ("布局", FindingKind::MarkupAttribute),
+                ("欢迎", FindingKind::MarkupText),
+                ("项", FindingKind::JsxText),
+            ]
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! Markup with `{expression}` or `{{ expression }}` interpolation, shared by the
+//! Svelte, Astro and mini-program (WXML/AXML) scanners.
+//!
+//! Text nodes and quoted attribute values are checked as text, interpolations
+//! and `name={...}` attribute values are scanned as expressions, and script
+//! elements (`<script>`, `<wxs>`) go through the oxc visitor. `<style>` is skipped.
+
+use super::file::{FileContext, FileScan};
+use super::js;
+use super::markup::{attribute_value, split_interpolations, Attribute, Segment, Token, Tokenizer};
+use oxc::allocator::Allocator;
+use oxc::span::SourceType;
+
+/// What differs between the markup flavours.
+pub(crate) struct Dialect {
+    /// Interpolation delimiters in text and quoted attribute values.
+    pub(crate) delimiters: (&'static str, &'static str),
+    /// Elements whose content is script, e.g. `script` or `wxs`.
+    pub(crate) script_tags: &'static [&'static str],
+    /// Source type of script elements without a `lang` attribute.
+    pub(crate) default_script: SourceType,
+    /// Source type of `{...}` expressions.
+    pub(crate) expression: SourceType,
+    /// Svelte logic blocks: `{#if}`, `{:else}`, `{/each}`, `{@html}`.
+    pub(crate) logic_blocks: bool,
+    /// Interpolations inside quoted attribute values are expressions (Svelte,
+    /// WXML), not text (Astro).
+    pub(crate) interpolate_quoted: bool,
+}
+
+/// Scans `ctx.source_text[start..end]` as markup.
+pub(crate) fn scan_markup(
+    ctx: &FileContext,
+    allocator: &Allocator,
+    start: usize,
+    end: usize,
+    dialect: &Dialect,
+    out: &mut FileScan,
+) {
+    let (open, close) = dialect.delimiters;
+    let mut tokenizer = Tokenizer::new(ctx.source_text, start, end).with_interpolation(open, close);
+
+    while let Some(token) = tokenizer.next() {
+        match token {
+            Token::StartTag {
+                name,
+                attributes,
+                self_closing: false,
+            } if dialect
+                .script_tags
+                .iter()
+                .any(|tag| name.eq_ignore_ascii_case(tag)) =>
+            {
+                let (code, start) = tokenizer.raw_text(name);
+                let lang = attribute_value(&attributes, "lang").map(|value| value.text);
+                let source_type = js::lang_source_type(lang, dialect.default_script);
+                out.extend(js::scan_script(
+                    ctx,
+                    allocator,
+                    code,
+                    start as u32,
+                    source_type,
+                ));
+            }
+            Token::StartTag {
+                name,
+                self_closing: false,
+                ..
+            } if name.eq_ignore_ascii_case("style") => {
+                tokenizer.raw_text("style");
+            }
+            Token::StartTag { attributes, .. } => {
+                for attribute in &attributes {
+                    scan_attribute(ctx, allocator, attribute, dialect, out);
+                }
+            }
+            Token::Text { text, start } => {
+                scan_interpolated(ctx, allocator, text, start, None, dialect, out);
+            }
+            Token::EndTag { .. } | Token::Comment => {}
+        }
+    }
+}
+
+fn scan_attribute(
+    ctx: &FileContext,
+    allocator: &Allocator,
+    attribute: &Attribute,
+    dialect: &Dialect,
+    out: &mut FileScan,
+) {
+    // `{shorthand}` and `{...spread}` attributes only hold identifiers.
+    let Some(value) = &attribute.value else {
+        return;
+    };
+    if value.braced {
+        scan_block(ctx, allocator, value.text, value.start, dialect, out);
+    } else if dialect.interpolate_quoted {
+        scan_interpolated(
+            ctx,
+            allocator,
+            value.text,
+            value.start,
+            Some(attribute.name),
+            dialect,
+            out,
+        );
+    } else {
+        ctx.check_markup(value.text, value.start as u32, Some(attribute.name), out);
+    }
+}
+
+/// Scans a text node, or the value of `attribute`, around its interpolations.
+fn scan_interpolated(
+    ctx: &FileContext,
+    allocator: &Allocator,
+    text: &str,
+    start: usize,
+    attribute: Option<&str>,
+    dialect: &Dialect,
+    out: &mut FileScan,
+) {
+    let (open, close) = dialect.delimiters;
+    for segment in split_interpolations(text, start, open, close) {
+        match segment {
+            Segment::Static { text, start } => {
+                ctx.check_markup(text, start as u32, attribute, out);
+            }
+            Segment::Expression { code, start } => {
+                scan_block(ctx, allocator, code, start, dialect, out);
+            }
+        }
+    }
+}
+
+/// Scans the inside of a `{...}`, unwrapping Svelte logic blocks first.
+fn scan_block(
+    ctx: &FileContext,
+    allocator: &Allocator,
+    code: &str,
+    start: usize,
+    dialect: &Dialect,
+    out: &mut FileScan,
+) {
+    let (code, start) = if dialect.logic_blocks {
+        match logic_block_expression(code) {
+            Some((offset, expression)) => (expression, start + offset),
+            None => return,
+        }
+    } else {
+        (code, start)
+    };
+    if code.trim().is_empty() {
+        return;
+    }
+    out.extend(js::scan_expression(
+        ctx,
+        allocator,
+        code,
+        start as u32,
+        dialect.expression,
+    ));
+}
+
+/// Returns the expression inside a Svelte tag and its offset in `code`, or
+/// `None` for tags without one such as `{/if}` or `{:else}`. Plain `{expr}`
+/// tags are returned as is.
+fn logic_block_expression(code: &str) -> Option<(usize, &str)> {
+    let trimmed = code.trim_start();
+    let lead = code.len() - trimmed.len();
+    let Some(sigil @ ('#' | ':' | '/' | '@')) = trimmed.chars().next() else {
+        return Some((0, code));
+    };
+    if sigil == '/' {
+        return None;
+    }
+
+    let keyword_end = trimmed[1..]
+        .find(|c: char| c.is_whitespace())
+        .map_or(trimmed.len(), |p| p + 1);
+    let keyword = &trimmed[1..keyword_end];
+    let mut rest_start = lead + keyword_end;
+    let mut rest = &code[rest_start..];
+
+    match (sigil, keyword) {
+        ('#', "if" | "key") | ('@', "html" | "render" | "const" | "debug") => {}
+        (':', "else") => {
+            // `{:else if cond}`
+            let after = rest.trim_start();
+            let cond = after.strip_prefix("if")?;
+            rest_start += rest.len() - cond.len();
+            rest = cond;
+        }
+        ('#', "each") => rest = &rest[..rest.find(" as ").unwrap_or(rest.len())],
+        ('#', "await") => rest = &rest[..rest.find(" then ").unwrap_or(rest.len())],
+        _ => return None,
+    }
+    Some((rest_start, rest))
+}
@@ -0,0 +1,657 @@
+//! Project config files: `chinese-scan.toml` or `.chinesescanrc` (JSON).
+//!
+//! The file in the scan root sets the defaults for the whole scan, and one in
+//! a subdirectory overrides them for that subtree. Settings are resolved per
+//! directory the first time a file in it is scanned, so a config file costs
+//! one lookup per directory and one compile per directory that has one.
+
+use super::file_type::{self, FileType, FileTypeMap};
+use super::locale::{self, LocaleMap};
+use super::script::{Script, ScriptSet};
+use super::{parse_size, ColumnUnit, ScanOptions};
+use ignore::overrides::{Override, OverrideBuilder};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// The contents of a config file. Globs are relative to the directory the
+/// file is in; rules are written as on the command line.
+///
+/// ```toml
+/// exclude = ["dist", "**/*.test.ts"]
+/// fileTypes = [".es6=js"]
+/// locales = ["i18n/*.json=en"]
+/// scripts = ["han", "katakana"]
+/// ignore = ["^https?://"]
+/// comments = true
+/// columns = "utf16"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ScanConfig {
+    /// Only files matching one of these globs are scanned. Replaces the
+    /// parent's list.
+    pub include: Option<Vec<String>>,
+    /// Globs not to scan, added to the parent's.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// `PATTERN=TYPE` rules, added after the parent's so they take precedence.
+    #[serde(default)]
+    pub file_types: Vec<String>,
+    pub skip_declarations: Option<bool>,
+    /// `PATTERN=LOCALE` rules, added after the parent's.
+    #[serde(default)]
+    pub locales: Vec<String>,
+    /// Locales whose files may contain Chinese, added to the parent's.
+    #[serde(default)]
+    pub chinese_locales: Vec<String>,
+    /// Script names, as for `--script`. Replaces the parent's list.
+    pub scripts: Option<Vec<String>>,
+    /// A custom regex, as for `--pattern`. Replaces the parent's.
+    pub pattern: Option<String>,
+    /// Regexes for text that is fine as it is: a finding whose text matches
+    /// one is dropped. Added to the parent's.
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    pub comments: Option<bool>,
+    pub identifiers: Option<bool>,
+    /// Reports each run of Chinese as its own finding.
+    pub split: Option<bool>,
+    /// Column unit, as for `--columns`. Only read from the root config.
+    pub columns: Option<String>,
+    /// Output format of the command line tool. Only read from the root config.
+    pub format: Option<String>,
+    /// Whether `.gitignore`, `.ignore` and git's excludes apply, `true` if
+    /// unset. Only read from the root config, as are the walk settings below.
+    pub gitignore: Option<bool>,
+    pub skip_hidden: Option<bool>,
+    pub follow_links: Option<bool>,
+    /// How many directories deep to walk; `1` scans the root's files only.
+    pub max_depth: Option<usize>,
+    /// Files larger than this are skipped: bytes, or with a `K`, `M` or `G`
+    /// suffix, e.g. `"1M"`.
+    pub max_file_size: Option<String>,
+}
+
+impl ScanConfig {
+    /// Config file names, in order of precedence when a directory has both.
+    pub const FILE_NAMES: [&'static str; 2] = ["chinese-scan.toml", ".chinesescanrc"];
+
+    /// Reads the config file in `dir`, if there is one.
+    pub fn find(dir: &Path) -> Result<Option<(PathBuf, ScanConfig)>, String> {
+        for name in Self::FILE_NAMES {
+            let path = dir.join(name);
+            if path.is_file() {
+                return Self::load(&path).map(|config| Some((path, config)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Reads a config file: TOML for a `.toml` file, JSON otherwise.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let config = if path.extension().is_some_and(|ext| ext == "toml") {
+            toml::from_str(&text).map_err(|e| e.to_string())
+        } else {
+            serde_json::from_str(&text).map_err(|e| e.to_string())
+        };
+        config.map_err(|e| format!("Invalid config {}: {}", path.display(), e))
+    }
+
+    /// The settings set here that only apply in the root config, as named
+    /// in the file.
+    fn root_only(&self) -> Vec<&'static str> {
+        [
+            ("columns", self.columns.is_some()),
+            ("format", self.format.is_some()),
+            ("gitignore", self.gitignore.is_some()),
+            ("skipHidden", self.skip_hidden.is_some()),
+            ("followLinks", self.follow_links.is_some()),
+            ("maxDepth", self.max_depth.is_some()),
+            ("maxFileSize", self.max_file_size.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(name, set)| set.then_some(name))
+        .collect()
+    }
+}
+
+/// The config files between the root and a directory, merged, with globs
+/// made relative to the root.
+#[derive(Clone, Default)]
+struct Settings {
+    include: Option<Vec<String>>,
+    exclude: Vec<String>,
+    file_types: Vec<(String, FileType)>,
+    skip_declarations: Option<bool>,
+    locales: Vec<(String, String)>,
+    chinese_locales: Vec<String>,
+    scripts: Option<Vec<Script>>,
+    pattern: Option<String>,
+    ignore: Vec<String>,
+    comments: Option<bool>,
+    identifiers: Option<bool>,
+    split: Option<bool>,
+}
+
+impl Settings {
+    /// These settings overridden by `config`, found in `dir` relative to the
+    /// root.
+    fn merge(&self, config: &ScanConfig, dir: &str) -> Result<Self, String> {
+        let mut merged = self.clone();
+        if let Some(include) = &config.include {
+            merged.include = Some(include.iter().map(|glob| rebase(dir, glob)).collect());
+        }
+        merged
+            .exclude
+            .extend(config.exclude.iter().map(|glob| rebase(dir, glob)));
+        for rule in &config.file_types {
+            let (pattern, file_type) = file_type::parse_rule(rule)?;
+            merged
+                .file_types
+                .push((rebase(dir, &file_type::glob_for(&pattern)), file_type));
+        }
+        for rule in &config.locales {
+            let (pattern, locale) = locale::parse_rule(rule)?;
+            merged.locales.push((rebase(dir, &pattern), locale));
+        }
+        merged
+            .chinese_locales
+            .extend(config.chinese_locales.iter().cloned());
+        if let Some(scripts) = &config.scripts {
+            let scripts = scripts
+                .iter()
+                .map(|name| name.parse())
+                .collect::<Result<_, String>>()?;
+            merged.scripts = Some(scripts);
+        }
+        if config.pattern.is_some() {
+            merged.pattern = config.pattern.clone();
+        }
+        merged.ignore.extend(config.ignore.iter().cloned());
+        merged.skip_declarations = config.skip_declarations.or(self.skip_declarations);
+        merged.comments = config.comments.or(self.comments);
+        merged.identifiers = config.identifiers.or(self.identifiers);
+        merged.split = config.split.or(self.split);
+        Ok(merged)
+    }
+}
+
+/// A glob from a config file in `dir`, relative to the root. As in
+/// `.gitignore`, a glob without a slash matches at any depth below `dir`.
+fn rebase(dir: &str, glob: &str) -> String {
+    if dir.is_empty() {
+        return glob.to_string();
+    }
+    let escaped: String = dir
+        .chars()
+        .map(|c| match c {
+            '*' | '?' | '[' | ']' | '{' | '}' => format!("[{}]", c),
+            '\\' => "/".to_string(),
+            _ => c.to_string(),
+        })
+        .collect();
+    let anchored = glob.trim_end_matches('/').contains('/');
+    match glob.strip_prefix('/') {
+        Some(glob) => format!("{}/{}", escaped, glob),
+        None if anchored => format!("{}/{}", escaped, glob),
+        None => format!("{}/**/{}", escaped, glob),
+    }
+}
+
+/// What applies to the files in one directory: its config files merged and
+/// the scan options on top, compiled.
+pub(crate) struct Profile {
+    /// The nearest config file, relative to the root.
+    pub(crate) config: Option<String>,
+    settings: Settings,
+    include: Option<Override>,
+    exclude: Option<Override>,
+    pub(crate) file_types: FileTypeMap,
+    pub(crate) locales: LocaleMap,
+    pub(crate) scripts: ScriptSet,
+    ignore: Vec<Regex>,
+    pub(crate) comments: bool,
+    pub(crate) identifiers: bool,
+    pub(crate) split_segments: bool,
+}
+
+impl Profile {
+    /// Options add their globs and rules after the config's and turn on what
+    /// they turn on; include globs from both are combined, and scripts and pattern set in the options replace the config's.
+    fn new(
+        root: &Path,
+        config: Option<String>,
+        settings: Settings,
+        options: &ScanOptions,
+    ) -> Result<Self, String> {
+        let overrides = |globs: &[String], prefix: &str| -> Result<Option<Override>, String> {
+            if globs.is_empty() {
+                return Ok(None);
+            }
+            let mut builder = OverrideBuilder::new(root);
+            for glob in globs {
+                builder
+                    .add(&format!("{}{}", prefix, glob))
+                    .map_err(|e| e.to_string())?;
+            }
+            builder.build().map(Some).map_err(|e| e.to_string())
+        };
+        let mut include = settings.include.clone().unwrap_or_default();
+        include.extend(options.include.iter().cloned());
+        let include = overrides(&include, "")?;
+        let mut exclude = settings.exclude.clone();
+        exclude.extend(options.exclude.iter().cloned());
+        let exclude = overrides(&exclude, "!")?;
+
+        let mut file_types = settings.file_types.clone();
+        file_types.extend(options.file_types.iter().cloned());
+        let skip_declarations =
+            options.skip_declarations || settings.skip_declarations.unwrap_or(false);
+        let mut locales = settings.locales.clone();
+        locales.extend(options.locales.iter().cloned());
+        let mut chinese_locales = options.chinese_locales.clone();
+        chinese_locales.extend(settings.chinese_locales.iter().cloned());
+
+        let scripts = options
+            .scripts
+            .as_ref()
+            .or(settings.scripts.as_ref())
+            .map_or(&[Script::Han][..], Vec::as_slice);
+        let pattern = options.pattern.as_ref().or(settings.pattern.as_ref());
+        let ignore = settings
+            .ignore
+            .iter()
+            .chain(&options.ignore_text)
+            .map(|pattern| {
+                Regex::new(pattern)
+                    .map_err(|e| format!("Invalid ignore pattern '{}': {}", pattern, e))
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self {
+            config,
+            include,
+            exclude,
+            file_types: FileTypeMap::new(root, &file_types, skip_declarations)?,
+            locales: LocaleMap::new(root, &locales, &chinese_locales)?,
+            scripts: ScriptSet::new(scripts, pattern.map(String::as_str))?,
+            ignore,
+            comments: options.comments || settings.comments.unwrap_or(false),
+            identifiers: options.identifiers || settings.identifiers.unwrap_or(false),
+            split_segments: options.split_segments || settings.split.unwrap_or(false),
+            settings,
+        })
+    }
+
+    /// Whether an exclude glob, from the options or a config, matches `path`,
+    /// a file or directory under the directory this profile is for.
+    pub(crate) fn excludes(&self, path: &Path, is_dir: bool) -> bool {
+        self.exclude
+            .as_ref()
+            .is_some_and(|exclude| exclude.matched(path, is_dir).is_ignore())
+    }
+
+    /// Whether there are include globs and the file at `path` matches none.
+    pub(crate) fn leaves_out(&self, path: &Path) -> bool {
+        self.include
+            .as_ref()
+            .is_some_and(|include| !include.matched(path, false).is_whitelist())
+    }
+
+    /// Whether `text` matches one of the ignore patterns.
+    pub(crate) fn ignores(&self, text: &str) -> bool {
+        self.ignore.iter().any(|pattern| pattern.is_match(text))
+    }
+}
+
+/// How the tree is walked: the options, or the root config where they leave
+/// a setting unset.
+pub(crate) struct WalkSettings {
+    pub(crate) gitignore: bool,
+    pub(crate) skip_hidden: bool,
+    pub(crate) follow_links: bool,
+    pub(crate) max_depth: Option<usize>,
+    pub(crate) max_file_size: Option<u64>,
+}
+
+impl WalkSettings {
+    fn new(options: &ScanOptions, config: Option<&ScanConfig>) -> Result<Self, String> {
+        let config_size = match config.and_then(|config| config.max_file_size.as_deref()) {
+            Some(size) => Some(parse_size(size)?),
+            None => None,
+        };
+        Ok(Self {
+            gitignore: options
+                .gitignore
+                .or(config.and_then(|config| config.gitignore))
+                .unwrap_or(true),
+            skip_hidden: options.skip_hidden
+                || config
+                    .and_then(|config| config.skip_hidden)
+                    .unwrap_or(false),
+            follow_links: options.follow_links
+                || config
+                    .and_then(|config| config.follow_links)
+                    .unwrap_or(false),
+            max_depth: options
+                .max_depth
+                .or(config.and_then(|config| config.max_depth)),
+            max_file_size: options.max_file_size.or(config_size),
+        })
+    }
+}
+
+/// The profile of every directory seen so far, shared by the walk's workers.
+pub(crate) struct ConfigTree<'a> {
+    root: &'a Path,
+    options: &'a ScanOptions,
+    /// The root config's column unit, unless the options set one.
+    pub(crate) column_unit: ColumnUnit,
+    pub(crate) walk: WalkSettings,
+    profiles: Mutex<HashMap<PathBuf, Arc<Profile>>>,
+}
+
+impl<'a> ConfigTree<'a> {
+    /// Reads the root config, if config files are enabled. Errors in it fail
+    /// the scan, like invalid options.
+    pub(crate) fn new(root: &'a Path, options: &'a ScanOptions) -> Result<Self, String> {
+        let found = if options.config {
+            ScanConfig::find(root)?
+        } else {
+            None
+        };
+        let mut column_unit = options.column_unit;
+        let (config, settings) = match &found {
+            Some((path, config)) => {
+                if column_unit.is_none() {
+                    column_unit = config
+                        .columns
+                        .as_deref()
+                        .map(str::parse)
+                        .transpose()
+                        .map_err(|e| format!("{}: {}", path.display(), e))?;
+                }
+                let settings = Settings::default()
+                    .merge(config, "")
+                    .map_err(|e| format!("{}: {}", path.display(), e))?;
+                (Some(relative(root, path)), settings)
+            }
+            None => (None, Settings::default()),
+        };
+        let walk =
+            WalkSettings::new(options, found.as_ref().map(|(_, config)| config)).map_err(|e| {
+                match &found {
+                    Some((path, _)) => format!("{}: {}", path.display(), e),
+                    None => e,
+                }
+            })?;
+        let profile = Profile::new(root, config, settings, options)?;
+        let profiles = HashMap::from([(root.to_path_buf(), Arc::new(profile))]);
+        Ok(Self {
+            root,
+            options,
+            column_unit: column_unit.unwrap_or_default(),
+            walk,
+            profiles: Mutex::new(profiles),
+        })
+    }
+
+    /// The profile for files in `dir`, and the first time, any problem with
+    /// a config file there. One that can't be used is skipped, its subtree
+    /// getting the parent's profile.
+    pub(crate) fn profile(&self, dir: &Path) -> (Arc<Profile>, Option<String>) {
+        if let Some(profile) = self.profiles.lock().unwrap().get(dir) {
+            return (Arc::clone(profile), None);
+        }
+        let parent = match dir.parent() {
+            Some(parent) if dir.starts_with(self.root) && dir != self.root => {
+                self.profile(parent).0
+            }
+            // Outside the root, which only a symlink could lead to.
+            _ => return self.profile(self.root),
+        };
+        let (profile, problem) = match self.nested(&parent, dir) {
+            Ok(Some((profile, warning))) => (Arc::new(profile), warning),
+            Ok(None) => (parent, None),
+            Err(error) => (parent, Some(error)),
+        };
+        let mut profiles = self.profiles.lock().unwrap();
+        match profiles.get(dir) {
+            // Another worker got there first and reported any error.
+            Some(profile) => (Arc::clone(profile), None),
+            None => {
+                profiles.insert(dir.to_path_buf(), Arc::clone(&profile));
+                (profile, problem)
+            }
+        }
+    }
+
+    /// The profile from a config file in `dir`, with a warning if it sets
+    /// what only the root config can.
+    fn nested(
+        &self,
+        parent: &Profile,
+        dir: &Path,
+    ) -> Result<Option<(Profile, Option<String>)>, String> {
+        if !self.options.config {
+            return Ok(None);
+        }
+        let Some((path, config)) = ScanConfig::find(dir)? else {
+            return Ok(None);
+        };
+        let settings = parent.settings.merge(&config, &relative(self.root, dir))?;
+        let profile = Profile::new(
+            self.root,
+            Some(relative(self.root, &path)),
+            settings,
+            self.options,
+        )
+        .map_err(|e| format!("{}: {}", relative(self.root, &path), e))?;
+        let root_only = config.root_only();
+        let warning = (!root_only.is_empty()).then(|| {
+            format!(
+                "{}: {} ignored, only read from the root config",
+                relative(self.root, &path),
+                root_only.join(", ")
+            )
+        });
+        Ok(Some((profile, warning)))
+    }
+
+    /// The config files in use, relative to the root, sorted.
+    pub(crate) fn configs(&self) -> Vec<String> {
+        let mut configs: Vec<String> = self
+            .profiles
+            .lock()
+            .unwrap()
+            .values()
+            .filter_map(|profile| profile.config.clone())
+            .collect();
+        configs.sort();
+        configs.dedup();
+        configs
+    }
+}
+
+fn relative(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rebase;
+    use crate::scanner::testing::TempTree;
+    use crate::scanner::{DiagnosticKind, ScanOptions, Scanner, SkipReason};
+
+    /// Each file a dry run plans, with why it is skipped, if it is.
+    fn planned(tree: &TempTree) -> Vec<(String, Option<SkipReason>)> {
+        let report = Scanner::dry_run(&ScanOptions::new(tree.path())).unwrap();
+        report
+            .files
+            .into_iter()
+            .map(|file| (file.file_path, file.skipped))
+            .collect()
+    }
+
+    #[test]
+    fn nested_include_scripts_and_pattern_replace_the_parents() {
+        let tree = TempTree::new(&[
+            (
+                "chinese-scan.toml",
+                "include = [\"*.js\"]\nscripts = [\"katakana\"]\npattern = \"TODO\"\n",
+            ),
+            (
+                "sub/chinese-scan.toml",
+                "include = [\"*.ts\"]\nscripts = [\"han\"]\npattern = \"FIXME\"\n",
+            ),
+            ("a.js", "f(\"カナ 汉字 TODO FIXME\");\n"),
+            ("a.ts", "f(\"汉字\");\n"),
+            ("sub/b.js", "f(\"汉字\");\n"),
+            ("sub/b.ts", "f(\"カナ 汉字 TODO FIXME\");\n"),
+        ]);
+        let files = planned(&tree);
+        assert!(files.contains(&("a.js".into(), None)));
+        assert!(files.contains(&("a.ts".into(), Some(SkipReason::NotIncluded))));
+        assert!(files.contains(&("sub/b.js".into(), Some(SkipReason::NotIncluded))));
+        assert!(files.contains(&("sub/b.ts".into(), None)));
+
+        let report = Scanner::scan(&ScanOptions::new(tree.path())).unwrap();
+        let runs: Vec<_> = report
+            .results
+            .iter()
+            .map(|result| {
+                let runs: Vec<_> = result.matches.iter().map(|run| run.text.as_str()).collect();
+                (result.file_path.as_str(), runs)
+            })
+            .collect();
+        assert_eq!(
+            runs,
+            [
+                ("a.js", vec!["カナ", "TODO"]),
+                ("sub/b.ts", vec!["汉字", "FIXME"])
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_lists_are_added_to_the_parents() {
+        let tree = TempTree::new(&[
+            (
+                "chinese-scan.toml",
+                "exclude = [\"*.gen.js\"]\nfileTypes = [\".es6=js\"]\nlocales = [\"*.lang.json=en\"]\nignore = [\"^忽略\"]\n",
+            ),
+            (
+                "sub/.chinesescanrc",
+                "{\"exclude\": [\"*.tmp.js\"], \"fileTypes\": [\".jsm=js\"], \"locales\": [\"*.l10n.json=fr\"], \"ignore\": [\"^跳过\"]}",
+            ),
+            ("sub/a.gen.js", "f(\"生成\");\n"),
+            ("sub/a.tmp.js", "f(\"临时\");\n"),
+            ("sub/a.es6", "f(\"忽略这个\", \"保留\");\n"),
+            ("sub/a.jsm", "f(\"跳过这个\", \"也保留\");\n"),
+            ("sub/a.lang.json", "{\"k\": \"英文\"}"),
+            ("sub/a.l10n.json", "{\"k\": \"法文\"}"),
+        ]);
+        let files = planned(&tree);
+        assert!(files.contains(&("sub/a.gen.js".into(), Some(SkipReason::Excluded))));
+        assert!(files.contains(&("sub/a.tmp.js".into(), Some(SkipReason::Excluded))));
+
+        let report = Scanner::scan(&ScanOptions::new(tree.path())).unwrap();
+        let found: Vec<_> = report
+            .results
+            .iter()
+            .map(|result| {
+                (
+                    result.file_path.as_str(),
+                    result.text.as_str(),
+                    result.locale.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("sub/a.es6", "保留", None),
+                ("sub/a.jsm", "也保留", None),
+                ("sub/a.l10n.json", "法文", Some("fr")),
+                ("sub/a.lang.json", "英文", Some("en")),
+            ]
+        );
+        assert_eq!(report.configs, ["chinese-scan.toml", "sub/.chinesescanrc"]);
+    }
+
+    #[test]
+    fn globs_are_rebased_onto_the_config_directory() {
+        assert_eq!(rebase("", "*.js"), "*.js");
+        assert_eq!(rebase("sub", "*.js"), "sub/**/*.js");
+        assert_eq!(rebase("sub", "gen/"), "sub/**/gen/");
+        assert_eq!(rebase("sub", "/top.js"), "sub/top.js");
+        assert_eq!(rebase("sub", "lib/*.js"), "sub/lib/*.js");
+        assert_eq!(rebase("a[1]", "*.js"), "a[[]1[]]/**/*.js");
+
+        let tree = TempTree::new(&[
+            ("sub/chinese-scan.toml", "exclude = [\"/top.js\"]\n"),
+            ("top.js", ""),
+            ("sub/top.js", ""),
+            ("sub/deep/top.js", ""),
+        ]);
+        let files = planned(&tree);
+        assert!(files.contains(&("top.js".into(), None)));
+        assert!(files.contains(&("sub/top.js".into(), Some(SkipReason::Excluded))));
+        assert!(files.contains(&("sub/deep/top.js".into(), None)));
+    }
+
+    #[test]
+    fn bad_nested_config_is_a_diagnostic() {
+        let tree = TempTree::new(&[
+            ("chinese-scan.toml", "exclude = [\"*.gen.js\"]\n"),
+            ("sub/chinese-scan.toml", "exclude = \"not a list\"\n"),
+            ("sub/a.js", "f(\"中文\");\n"),
+            ("sub/a.gen.js", "f(\"生成\");\n"),
+        ]);
+        let report = Scanner::scan(&ScanOptions::new(tree.path())).unwrap();
+        assert_eq!(report.diagnostics.len(), 1);
+        assert_eq!(report.diagnostics[0].kind, DiagnosticKind::Config);
+        assert_eq!(report.diagnostics[0].file_path, "sub");
+        // The subtree falls back to the root config.
+        let files: Vec<_> = report
+            .results
+            .iter()
+            .map(|result| result.file_path.as_str())
+            .collect();
+        assert_eq!(files, ["sub/a.js"]);
+    }
+
+    #[test]
+    fn nested_walk_settings_are_reported_and_ignored() {
+        let tree = TempTree::new(&[
+            (
+                "sub/chinese-scan.toml",
+                "comments = true\nmaxDepth = 1\nskipHidden = true\n",
+            ),
+            ("sub/a.js", "// 注释\n"),
+            ("sub/deep/b.js", "f(\"中文\");\n"),
+        ]);
+        let report = Scanner::scan(&ScanOptions::new(tree.path())).unwrap();
+        assert_eq!(report.diagnostics.len(), 1);
+        assert_eq!(report.diagnostics[0].kind, DiagnosticKind::Config);
+        assert_eq!(
+            report.diagnostics[0].message,
+            "sub/chinese-scan.toml: skipHidden, maxDepth ignored, only read from the root config"
+        );
+        // The rest of the config still applies.
+        let files: Vec<_> = report
+            .results
+            .iter()
+            .map(|result| result.file_path.as_str())
+            .collect();
+        assert_eq!(files, ["sub/a.js", "sub/deep/b.js"]);
+    }
+}
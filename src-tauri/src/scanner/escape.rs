@@ -0,0 +1,225 @@
+//! Maps decoded string values back to the source they were decoded from, so
+//! a finding's range covers the characters as written, escapes included.
+//!
+//! Rather than decoding each format's escapes again, the decoded value is
+//! walked alongside the raw source: a character written as itself advances
+//! the raw position by its length, and at a backslash the escape that decodes
+//! to the expected character is looked for. That covers `\n`, `\xHH`,
+//! `\uHHHH` (with surrogate pairs, or Java's extra `u`s), `\u{H}`,
+//! `\UHHHHHHHH`, octal escapes and line continuations across JS, JSON, YAML,
+//! properties and `.strings` files and Java, Go, Python and Kotlin strings.
+//! Go's `\xHH` escapes spelling out UTF-8 bytes don't align; the finding then
+//! covers the whole string.
+
+use std::ops::Range;
+
+/// Where each character of a decoded value is in its raw source.
+pub(crate) struct RawMap {
+    /// Per character: its byte offset in the decoded value and its byte
+    /// range in the raw source.
+    chars: Vec<(usize, Range<usize>)>,
+    cooked_len: usize,
+    raw_len: usize,
+}
+
+impl RawMap {
+    /// Aligns `cooked` with `raw`. With `escapes`, a backslash in `raw`
+    /// starts an escape sequence. Returns `None` when the two can't be
+    /// aligned, e.g. when a format folds whitespace or strips quotes.
+    pub(crate) fn new(cooked: &str, raw: &str, escapes: bool) -> Option<Self> {
+        let mut chars = Vec::with_capacity(cooked.len());
+        let mut r = 0;
+        for (index, c) in cooked.char_indices() {
+            loop {
+                let rest = &raw[r..];
+                if escapes && rest.starts_with('\\') {
+                    if let Some(len) = escape_len(rest, c) {
+                        chars.push((index, r..r + len));
+                        r += len;
+                        break;
+                    }
+                    if let Some(len) = continuation_len(rest) {
+                        r += len;
+                        continue;
+                    }
+                }
+                // An escape the format keeps as written, such as Python's
+                // `\N{...}`, matches its backslash here.
+                if rest.starts_with(c) {
+                    chars.push((index, r..r + c.len_utf8()));
+                    r += c.len_utf8();
+                    break;
+                } else if c == '\n' && rest.starts_with("\r\n") {
+                    // Template literals normalize line endings.
+                    chars.push((index, r..r + 2));
+                    r += 2;
+                    break;
+                } else {
+                    return None;
+                }
+            }
+        }
+        Some(Self {
+            chars,
+            cooked_len: cooked.len(),
+            raw_len: raw.len(),
+        })
+    }
+
+    /// The raw range of the decoded bytes `range`, which must fall on
+    /// character boundaries.
+    pub(crate) fn range(&self, range: Range<usize>) -> Range<usize> {
+        let start = self.raw_start(range.start);
+        let end = if range.end >= self.cooked_len {
+            self.chars.last().map_or(self.raw_len, |(_, raw)| raw.end)
+        } else {
+            self.raw_start(range.end)
+        };
+        start..end.max(start)
+    }
+
+    fn raw_start(&self, index: usize) -> usize {
+        match self
+            .chars
+            .binary_search_by_key(&index, |(cooked, _)| *cooked)
+        {
+            Ok(i) => self.chars[i].1.start,
+            Err(i) => self.chars.get(i).map_or(self.raw_len, |(_, raw)| raw.start),
+        }
+    }
+}
+
+/// Length of the escape sequence at the start of `raw` if it decodes to `c`.
+fn escape_len(raw: &str, c: char) -> Option<usize> {
+    let mut chars = raw[1..].chars();
+    let next = chars.next()?;
+    let simple = match next {
+        'n' => Some('\n'),
+        't' => Some('\t'),
+        'r' => Some('\r'),
+        'b' => Some('\u{8}'),
+        'f' => Some('\u{c}'),
+        'v' => Some('\u{b}'),
+        'a' => Some('\u{7}'),
+        'e' => Some('\u{1b}'),
+        _ => None,
+    };
+    if simple == Some(c) || next == c {
+        return Some(1 + next.len_utf8());
+    }
+    let hex = |from: usize, len: usize| {
+        let digits = raw.get(from..from + len)?;
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        u32::from_str_radix(digits, 16).ok()
+    };
+    match next {
+        'x' if hex(2, 2).and_then(char::from_u32) == Some(c) => Some(4),
+        'u' if raw[2..].starts_with('{') => {
+            let close = raw.find('}')?;
+            (hex(3, close - 3).and_then(char::from_u32) == Some(c)).then_some(close + 1)
+        }
+        'u' => {
+            // Java allows any number of `u`s.
+            let us = raw[1..].len() - raw[1..].trim_start_matches('u').len();
+            let len = 1 + us + 4;
+            let unit = hex(1 + us, 4)?;
+            if char::from_u32(unit) == Some(c) {
+                return Some(len);
+            }
+            // A surrogate pair written as two escapes.
+            let low = raw[len..].strip_prefix("\\u").and(hex(len + 2, 4))?;
+            let combined =
+                0x10000 + ((unit.checked_sub(0xd800)?) << 10) + low.checked_sub(0xdc00)?;
+            (char::from_u32(combined) == Some(c)).then_some(len + 6)
+        }
+        // `\UHHHHHHHH` in YAML, `\UHHHH` in `.strings` files.
+        'U' => [8, 4]
+            .into_iter()
+            .find(|&len| hex(2, len).and_then(char::from_u32) == Some(c))
+            .map(|len| 2 + len),
+        '0'..='7' => (1..=3).find_map(|len| {
+            let digits = raw.get(1..1 + len)?;
+            let value = u32::from_str_radix(digits, 8).ok()?;
+            (char::from_u32(value) == Some(c)).then_some(1 + len)
+        }),
+        _ => None,
+    }
+}
+
+/// Length of a backslash line continuation at the start of `raw`, which
+/// decodes to nothing.
+fn continuation_len(raw: &str) -> Option<usize> {
+    let rest = &raw[1..];
+    ["\r\n", "\n", "\r", "\u{2028}", "\u{2029}"]
+        .into_iter()
+        .find(|newline| rest.starts_with(newline))
+        .map(|newline| 1 + newline.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RawMap;
+    use std::ops::Range;
+
+    /// The raw range of the cooked bytes `range`, aligned with escapes on.
+    fn raw_range(cooked: &str, raw: &str, range: Range<usize>) -> Range<usize> {
+        RawMap::new(cooked, raw, true).unwrap().range(range)
+    }
+
+    #[test]
+    fn unicode_escape() {
+        let raw = "\\u4e2d";
+        assert_eq!(raw_range("中", raw, 0..3), 0..6);
+        let raw = "a\\u4e2db";
+        assert_eq!(raw_range("a中b", raw, 1..4), 1..7);
+    }
+
+    #[test]
+    fn braced_unicode_escape() {
+        assert_eq!(raw_range("中文", r"\u{4e2d}文", 0..3), 0..8);
+        assert_eq!(raw_range("中文", r"\u{4e2d}文", 3..6), 8..11);
+    }
+
+    #[test]
+    fn surrogate_pair() {
+        let raw = "\\uD83D\\uDE00中";
+        assert_eq!(raw_range("😀中", raw, 0..4), 0..12);
+        assert_eq!(raw_range("😀中", raw, 4..7), 12..15);
+    }
+
+    #[test]
+    fn hex_escape_between_runs() {
+        let (cooked, raw) = ("中A文", r"中\x41文");
+        assert_eq!(raw_range(cooked, raw, 0..3), 0..3);
+        assert_eq!(raw_range(cooked, raw, 3..4), 3..7);
+        assert_eq!(raw_range(cooked, raw, 4..7), 7..10);
+    }
+
+    #[test]
+    fn line_continuation() {
+        assert_eq!(raw_range("中文", "中\\\n文", 3..6), 5..8);
+        assert_eq!(raw_range("中文", "中\\\r\n文", 0..6), 0..9);
+    }
+
+    #[test]
+    fn normalized_line_ending() {
+        assert_eq!(raw_range("中\n文", "中\r\n文", 4..7), 5..8);
+    }
+
+    #[test]
+    fn octal_and_long_unicode_escapes() {
+        assert_eq!(raw_range("A中", r"\101中", 1..4), 4..7);
+        assert_eq!(raw_range("中", r"\U00004E2D", 0..3), 0..10);
+    }
+
+    #[test]
+    fn unaligned_is_none() {
+        // Folded whitespace, and an escape that decodes to something else.
+        assert!(RawMap::new("中 文", "中  文", true).is_none());
+        assert!(RawMap::new("中", "\\u4e2e", true).is_none());
+        // Without escapes a backslash is itself.
+        assert!(RawMap::new("中", "\\u4e2d", false).is_none());
+    }
+}
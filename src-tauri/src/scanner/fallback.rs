@@ -0,0 +1,130 @@
+//! Token-level scanning for files oxc could not parse at all.
+//!
+//! This doesn't build an AST. It only tracks enough state to tell code from
+//! comments, quoted strings and template literals, and treats Han text found
+//! in code between `>`/`}` and `<`/`{` as JSX-like text. Findings are marked
+//! [`Confidence::Low`] since regex literals and unusual syntax can confuse it.
+
+use super::file::FileContext;
+use super::{Confidence, FindingKind, ScanResult};
+
+enum State {
+    /// Ordinary code; the counter tracks `{` nesting inside a `${ }` substitution.
+    Code(usize),
+    /// Inside a template literal, outside any substitution.
+    Template,
+}
+
+/// Scans `source_text`, which starts at `base_offset` in the file.
+pub(crate) fn scan_tokens(
+    ctx: &FileContext,
+    source_text: &str,
+    base_offset: u32,
+) -> Vec<ScanResult> {
+    let bytes = source_text.as_bytes();
+    let mut results = Vec::new();
+    let mut stack = vec![State::Code(0)];
+    let mut segment_start = 0;
+    let mut i = 0;
+
+    // Text between tags is trimmed; string and template contents are not.
+    let mut report = |start: usize, end: usize, kind: FindingKind| {
+        let trim = kind == FindingKind::JsxText;
+        let raw = &source_text[start..end];
+        let text = if trim { raw.trim() } else { raw };
+        let segments = ctx.segments(raw, base_offset as usize + start);
+        if !segments.is_empty() {
+            results.push(ctx.result(segments, text, Confidence::Low, kind));
+        }
+    };
+
+    while i < bytes.len() {
+        let in_substitution = stack.len() > 1;
+        match stack.last_mut() {
+            Some(State::Template) => match bytes[i] {
+                b'\\' => i += 2,
+                b'`' => {
+                    report(segment_start, i, FindingKind::TemplateLiteral);
+                    stack.pop();
+                    i += 1;
+                    segment_start = i;
+                }
+                b'$' if bytes.get(i + 1) == Some(&b'{') => {
+                    report(segment_start, i, FindingKind::TemplateLiteral);
+                    stack.push(State::Code(0));
+                    i += 2;
+                    segment_start = i;
+                }
+                _ => i += 1,
+            },
+            Some(State::Code(depth)) => match bytes[i] {
+                b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                    report(segment_start, i, FindingKind::JsxText);
+                    i = find_byte(bytes, b'\n', i).unwrap_or(bytes.len());
+                    segment_start = i;
+                }
+                b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                    report(segment_start, i, FindingKind::JsxText);
+                    i = source_text[i + 2..]
+                        .find("*/")
+                        .map_or(bytes.len(), |end| i + 2 + end + 2);
+                    segment_start = i;
+                }
+                quote @ (b'"' | b'\'') => {
+                    report(segment_start, i, FindingKind::JsxText);
+                    let start = i + 1;
+                    let mut end = start;
+                    while end < bytes.len() && bytes[end] != quote && bytes[end] != b'\n' {
+                        end += if bytes[end] == b'\\' { 2 } else { 1 };
+                    }
+                    let end = end.min(bytes.len());
+                    report(start, end, FindingKind::StringLiteral);
+                    i = end + 1;
+                    segment_start = i;
+                }
+                b'`' => {
+                    report(segment_start, i, FindingKind::JsxText);
+                    stack.push(State::Template);
+                    i += 1;
+                    segment_start = i;
+                }
+                b'{' => {
+                    report(segment_start, i, FindingKind::JsxText);
+                    *depth += 1;
+                    i += 1;
+                    segment_start = i;
+                }
+                b'}' => {
+                    report(segment_start, i, FindingKind::JsxText);
+                    if *depth == 0 && in_substitution {
+                        // End of a `${ }` substitution, back into the template.
+                        stack.pop();
+                    } else {
+                        *depth = depth.saturating_sub(1);
+                    }
+                    i += 1;
+                    segment_start = i;
+                }
+                b'<' | b'>' => {
+                    report(segment_start, i, FindingKind::JsxText);
+                    i += 1;
+                    segment_start = i;
+                }
+                _ => i += 1,
+            },
+            None => break,
+        }
+    }
+    if matches!(stack.last(), Some(State::Code(_))) && segment_start < bytes.len() {
+        report(segment_start, bytes.len(), FindingKind::JsxText);
+    }
+
+    results
+}
+
+fn find_byte(bytes: &[u8], needle: u8, from: usize) -> Option<usize> {
+    bytes[from..]
+        .iter()
+        .position(|&b| b == needle)
+        .map(|pos| from + pos)
+}
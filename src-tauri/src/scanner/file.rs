@@ -0,0 +1,326 @@
+use super::escape::RawMap;
+use super::position::LineIndex;
+use super::script::ScriptSet;
+use super::segment;
+use super::{
+    Confidence, DiagnosticKind, FindingContext, FindingKind, MatchRange, ScanDiagnostic, ScanResult,
+};
+use regex::Regex;
+use std::ops::Range;
+
+/// The file being scanned, shared by every extractor that looks at part of it.
+///
+/// Offsets and ranges handed to [`FileContext::result`] and
+/// [`FileContext::diagnostic`] are byte offsets into `source_text`, the whole file, so findings in embedded
+/// regions (a Vue `<script>` block, a template expression) map back to the
+/// original file.
+pub(crate) struct FileContext<'a> {
+    /// Path relative to the scan root, as shown in results.
+    pub(crate) file_path: &'a str,
+    pub(crate) source_text: &'a str,
+    /// Matches any character of the scripts scanned for, Han by default.
+    pub(crate) chinese_regex: &'a Regex,
+    pub(crate) scripts: &'a ScriptSet,
+    /// Maps offsets in `source_text` to lines and columns.
+    pub(crate) lines: LineIndex,
+    /// Whether comments are scanned, for the languages that support it.
+    pub(crate) comments: bool,
+    /// Whether identifiers, property keys, import specifiers and the file
+    /// path are checked too.
+    pub(crate) identifiers: bool,
+}
+
+impl FileContext<'_> {
+    /// A finding made of `segments`, which must be in order. Its range runs
+    /// from the first segment to the end of the last.
+    pub(crate) fn result(
+        &self,
+        segments: Vec<Segment>,
+        text: &str,
+        confidence: Confidence,
+        kind: FindingKind,
+    ) -> ScanResult {
+        let start = segments.first().map_or(0, |segment| segment.range.start);
+        let end = segments.last().map_or(start, |segment| segment.range.end);
+        let (line, column) = self.lines.line_col(self.source_text, start);
+        let (end_line, end_column) = self.lines.line_col(self.source_text, end);
+        let matches = segments
+            .into_iter()
+            .map(|segment| self.match_range(segment))
+            .collect();
+        ScanResult {
+            file_path: self.file_path.to_string(),
+            line,
+            column,
+            end_line,
+            end_column,
+            start_offset: start,
+            end_offset: end,
+            text: text.to_string(),
+            matches,
+            scripts: self.scripts.scripts_in(text),
+            confidence,
+            kind,
+            context: FindingContext::default(),
+            key: None,
+            locale: None,
+            config: None,
+        }
+    }
+
+    fn match_range(&self, segment: Segment) -> MatchRange {
+        let (line, column) = self.lines.line_col(self.source_text, segment.range.start);
+        let (end_line, end_column) = self.lines.line_col(self.source_text, segment.range.end);
+        MatchRange {
+            text: segment.text,
+            line,
+            column,
+            end_line,
+            end_column,
+            start_offset: segment.range.start,
+            end_offset: segment.range.end,
+        }
+    }
+
+    pub(crate) fn diagnostic(
+        &self,
+        kind: DiagnosticKind,
+        message: String,
+        offset: Option<u32>,
+    ) -> ScanDiagnostic {
+        let (line, column) = match offset {
+            Some(offset) => {
+                let (line, column) = self.lines.line_col(self.source_text, offset as usize);
+                (Some(line), Some(column))
+            }
+            None => (None, None),
+        };
+        ScanDiagnostic {
+            file_path: self.file_path.to_string(),
+            kind,
+            message,
+            line,
+            column,
+        }
+    }
+
+    /// The runs of Chinese in `text`, which starts at `offset` in the file.
+    pub(crate) fn segments(&self, text: &str, offset: usize) -> Vec<Segment> {
+        segment::segments(self.chinese_regex, text)
+            .into_iter()
+            .map(|range| Segment {
+                text: text[range.clone()].to_string(),
+                range: offset + range.start..offset + range.end,
+            })
+            .collect()
+    }
+
+    /// Like [`FileContext::segments`] for a value decoded from `raw`, which
+    /// starts at `offset`: each range covers the Chinese as written in `raw`,
+    /// escapes such as `\u4e2d` included, while the text is decoded. When the
+    /// value can't be mapped back, the Chinese written as such in `raw` is
+    /// used, or all of `raw` if there is none.
+    pub(crate) fn raw_segments(
+        &self,
+        cooked: &str,
+        raw: &str,
+        offset: usize,
+        escapes: bool,
+    ) -> Vec<Segment> {
+        let ranges = segment::segments(self.chinese_regex, cooked);
+        if ranges.is_empty() {
+            return Vec::new();
+        }
+        match RawMap::new(cooked, raw, escapes) {
+            Some(map) => ranges
+                .into_iter()
+                .map(|range| {
+                    let text = cooked[range.clone()].to_string();
+                    let range = map.range(range);
+                    Segment {
+                        text,
+                        range: offset + range.start..offset + range.end,
+                    }
+                })
+                .collect(),
+            None => {
+                let segments = self.segments(raw, offset);
+                if segments.is_empty() {
+                    vec![Segment {
+                        text: cooked.trim().to_string(),
+                        range: offset..offset + raw.len(),
+                    }]
+                } else {
+                    segments
+                }
+            }
+        }
+    }
+
+    /// Reports `text`, which starts at `offset`, if it contains Chinese. The
+    /// range is that of the Chinese in it; `text` is reported trimmed.
+    pub(crate) fn check_text(
+        &self,
+        text: &str,
+        offset: u32,
+        confidence: Confidence,
+        kind: FindingKind,
+        out: &mut FileScan,
+    ) {
+        let segments = self.segments(text, offset as usize);
+        if !segments.is_empty() {
+            out.results
+                .push(self.result(segments, text.trim(), confidence, kind));
+        }
+    }
+
+    /// Checks a text node, or the value of `attribute`, in a template.
+    pub(crate) fn check_markup(
+        &self,
+        text: &str,
+        offset: u32,
+        attribute: Option<&str>,
+        out: &mut FileScan,
+    ) {
+        let kind = match attribute {
+            Some(_) => FindingKind::MarkupAttribute,
+            None => FindingKind::MarkupText,
+        };
+        let len = out.results.len();
+        self.check_text(text, offset, Confidence::High, kind, out);
+        if let Some(result) = out.results.get_mut(len) {
+            result.context.property = attribute.map(String::from);
+        }
+    }
+
+    /// Reports a comment's `text`, which starts at `offset` and excludes the
+    /// comment markers, if it contains Chinese. The `*` starting each line of
+    /// a `/** */` doc comment is dropped from the reported text.
+    pub(crate) fn check_comment(&self, text: &str, offset: u32, out: &mut FileScan) {
+        let segments = self.segments(text, offset as usize);
+        if segments.is_empty() {
+            return;
+        }
+        let lines: Vec<&str> = text
+            .lines()
+            .map(|line| line.trim().trim_start_matches('*').trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+        out.results.push(self.result(
+            segments,
+            &lines.join("\n"),
+            Confidence::High,
+            FindingKind::Comment,
+        ));
+    }
+
+    /// Reports a name such as an identifier, which starts at `offset`, as a
+    /// finding of `kind` if it contains Chinese.
+    pub(crate) fn check_name(
+        &self,
+        name: &str,
+        offset: u32,
+        kind: FindingKind,
+        out: &mut Vec<ScanResult>,
+    ) {
+        let segments = self.segments(name, offset as usize);
+        if !segments.is_empty() {
+            out.push(self.result(segments, name, Confidence::High, kind));
+        }
+    }
+
+    /// Reports a resource value if it contains Chinese. `raw` is the value as
+    /// written, starting at `start`, and `value` it with escapes decoded. The
+    /// range is mapped back to `raw` by [`FileContext::raw_segments`].
+    pub(crate) fn check_value(
+        &self,
+        value: &str,
+        raw: &str,
+        start: usize,
+        key: &str,
+        out: &mut FileScan,
+    ) {
+        let segments = self.raw_segments(value, raw, start, true);
+        self.check_segments(segments, value, key, out);
+    }
+
+    /// Reports a resource value written in several pieces, such as a PO
+    /// string continued over lines, from the runs found in each piece.
+    pub(crate) fn check_segments(
+        &self,
+        segments: Vec<Segment>,
+        value: &str,
+        key: &str,
+        out: &mut FileScan,
+    ) {
+        if segments.is_empty() {
+            return;
+        }
+        let mut result = self.result(
+            segments,
+            value.trim(),
+            Confidence::High,
+            FindingKind::Resource,
+        );
+        result.key = Some(key.to_string());
+        out.results.push(result);
+    }
+}
+
+/// A run of Chinese in a finding: its text, decoded, and its byte range in
+/// the file.
+pub(crate) struct Segment {
+    pub(crate) text: String,
+    pub(crate) range: Range<usize>,
+}
+
+/// What scanning a single file, or one region of it, produced.
+#[derive(Default)]
+pub(crate) struct FileScan {
+    pub(crate) results: Vec<ScanResult>,
+    pub(crate) diagnostics: Vec<ScanDiagnostic>,
+}
+
+impl FileScan {
+    pub(crate) fn extend(&mut self, other: FileScan) {
+        self.results.extend(other.results);
+        self.diagnostics.extend(other.diagnostics);
+    }
+}
+
+/// Runs `scan` over `source_text` looking for Han, as a file named `test`,
+/// for extractors' unit tests.
+#[cfg(test)]
+pub(crate) fn scan_test_source(
+    source_text: &str,
+    scan: impl FnOnce(&FileContext) -> FileScan,
+) -> Vec<ScanResult> {
+    use super::{ColumnUnit, Script};
+
+    let scripts = ScriptSet::new(&[Script::Han], None).unwrap();
+    let ctx = FileContext {
+        file_path: "test",
+        source_text,
+        chinese_regex: &scripts.regex,
+        scripts: &scripts,
+        lines: LineIndex::new(source_text, ColumnUnit::Byte),
+        comments: false,
+        identifiers: false,
+    };
+    scan(&ctx).results
+}
+
+/// The finding under `key`, for resource readers' unit tests.
+#[cfg(test)]
+pub(crate) fn keyed<'a>(results: &'a [ScanResult], key: &str) -> &'a ScanResult {
+    results
+        .iter()
+        .find(|result| result.key.as_deref() == Some(key))
+        .unwrap_or_else(|| panic!("no finding under '{}'", key))
+}
+
+/// The source a finding's range covers, escapes and all.
+#[cfg(test)]
+pub(crate) fn covered<'a>(source_text: &'a str, result: &ScanResult) -> &'a str {
+    &source_text[result.start_offset..result.end_offset]
+}
@@ -0,0 +1,376 @@
+//! Which scanner handles a file: the built-in mapping from extension, shebang
+//! detection for extensionless scripts, and user rules that override both.
+
+use super::syntax::Grammar;
+use super::{miniprogram, Language};
+use ignore::overrides::{Override, OverrideBuilder};
+use oxc::span::SourceType;
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+
+/// How a file is scanned. Parsed from and displayed as its [`FileType::name`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileType {
+    /// JavaScript with JSX, either an ES module or a classic script: `.js`.
+    /// Parsed as a module, or as a script when that parses with fewer errors.
+    JavaScript,
+    /// JavaScript ES module: `.mjs`, Alipay `.sjs`.
+    JavaScriptModule,
+    /// CommonJS without JSX: `.cjs`, WeChat `.wxs`, node scripts.
+    CommonJs,
+    Jsx,
+    /// `.ts`, `.mts` and `.cts`.
+    TypeScript,
+    Tsx,
+    /// A declaration file. Only picked by a rule; `.d.ts` files are scanned as
+    /// TypeScript unless skipped with [`ScanOptions::skip_declarations`].
+    ///
+    /// [`ScanOptions::skip_declarations`]: super::ScanOptions::skip_declarations
+    TypeScriptDeclaration,
+    Vue,
+    Svelte,
+    Astro,
+    /// WeChat `.wxml` or Alipay `.axml` template.
+    MiniProgramTemplate,
+    /// Mini-program page, component or app `.json` config.
+    MiniProgramConfig,
+    Html,
+    Ejs,
+    Handlebars,
+    Pug,
+    /// Locale resources. JSON and YAML files are only picked by extension
+    /// when they have a locale; `.properties` and `.po` files always are.
+    Json,
+    Yaml,
+    Properties,
+    Po,
+    /// Android resources in a `res/values*` directory, such as `strings.xml`.
+    AndroidStrings,
+    /// iOS and macOS `.strings` table.
+    AppleStrings,
+    /// iOS and macOS `.stringsdict` plural rules.
+    StringsDict,
+    /// Xcode `.xcstrings` string catalog, holding every locale in one file.
+    StringCatalog,
+    /// Flutter `.arb` bundle.
+    Arb,
+    Java,
+    Go,
+    /// `.py` and `.pyi`, and scripts with a `python` `#!` line.
+    Python,
+    /// `.kt` and `.kts`.
+    Kotlin,
+}
+
+impl FileType {
+    pub const ALL: [FileType; 29] = [
+        FileType::JavaScript,
+        FileType::JavaScriptModule,
+        FileType::CommonJs,
+        FileType::Jsx,
+        FileType::TypeScript,
+        FileType::Tsx,
+        FileType::TypeScriptDeclaration,
+        FileType::Vue,
+        FileType::Svelte,
+        FileType::Astro,
+        FileType::MiniProgramTemplate,
+        FileType::MiniProgramConfig,
+        FileType::Html,
+        FileType::Ejs,
+        FileType::Handlebars,
+        FileType::Pug,
+        FileType::Json,
+        FileType::Yaml,
+        FileType::Properties,
+        FileType::Po,
+        FileType::AndroidStrings,
+        FileType::AppleStrings,
+        FileType::StringsDict,
+        FileType::StringCatalog,
+        FileType::Arb,
+        FileType::Java,
+        FileType::Go,
+        FileType::Python,
+        FileType::Kotlin,
+    ];
+
+    /// The name used in `PATTERN=TYPE` rules, mostly the usual extension.
+    pub fn name(self) -> &'static str {
+        match self {
+            FileType::JavaScript => "js",
+            FileType::JavaScriptModule => "mjs",
+            FileType::CommonJs => "cjs",
+            FileType::Jsx => "jsx",
+            FileType::TypeScript => "ts",
+            FileType::Tsx => "tsx",
+            FileType::TypeScriptDeclaration => "dts",
+            FileType::Vue => "vue",
+            FileType::Svelte => "svelte",
+            FileType::Astro => "astro",
+            FileType::MiniProgramTemplate => "wxml",
+            FileType::MiniProgramConfig => "miniprogram-json",
+            FileType::Html => "html",
+            FileType::Ejs => "ejs",
+            FileType::Handlebars => "hbs",
+            FileType::Pug => "pug",
+            FileType::Json => "json",
+            FileType::Yaml => "yaml",
+            FileType::Properties => "properties",
+            FileType::Po => "po",
+            FileType::AndroidStrings => "android",
+            FileType::AppleStrings => "strings",
+            FileType::StringsDict => "stringsdict",
+            FileType::StringCatalog => "xcstrings",
+            FileType::Arb => "arb",
+            FileType::Java => "java",
+            FileType::Go => "go",
+            FileType::Python => "py",
+            FileType::Kotlin => "kt",
+        }
+    }
+
+    /// Locale resource files, whose findings have a key and a locale.
+    pub fn is_resource(self) -> bool {
+        matches!(
+            self,
+            FileType::Json
+                | FileType::Yaml
+                | FileType::Properties
+                | FileType::Po
+                | FileType::AndroidStrings
+                | FileType::AppleStrings
+                | FileType::StringsDict
+                | FileType::StringCatalog
+                | FileType::Arb
+        )
+    }
+
+    pub(crate) fn language(self) -> Language {
+        let module = SourceType::default().with_module(true);
+        match self {
+            FileType::JavaScript => Language::JavaScript,
+            FileType::JavaScriptModule => Language::Script(module.with_jsx(true)),
+            FileType::CommonJs => Language::Script(SourceType::default()),
+            FileType::Jsx => Language::Script(module.with_jsx(true)),
+            FileType::TypeScript => Language::Script(module.with_typescript(true)),
+            FileType::Tsx => Language::Script(module.with_typescript(true).with_jsx(true)),
+            FileType::TypeScriptDeclaration => {
+                Language::Script(module.with_typescript_definition(true))
+            }
+            FileType::Vue => Language::Vue,
+            FileType::Svelte => Language::Svelte,
+            FileType::Astro => Language::Astro,
+            FileType::MiniProgramTemplate => Language::MiniProgramTemplate,
+            FileType::MiniProgramConfig => Language::MiniProgramConfig,
+            FileType::Html => Language::Html,
+            FileType::Ejs => Language::Ejs,
+            FileType::Handlebars => Language::Handlebars,
+            FileType::Pug => Language::Pug,
+            FileType::Json => Language::Json,
+            FileType::Yaml => Language::Yaml,
+            FileType::Properties => Language::Properties,
+            FileType::Po => Language::Po,
+            FileType::AndroidStrings => Language::AndroidStrings,
+            FileType::AppleStrings => Language::AppleStrings,
+            FileType::StringsDict => Language::StringsDict,
+            FileType::StringCatalog => Language::StringCatalog,
+            FileType::Arb => Language::Arb,
+            FileType::Java => Language::Syntax(Grammar::Java),
+            FileType::Go => Language::Syntax(Grammar::Go),
+            FileType::Python => Language::Syntax(Grammar::Python),
+            FileType::Kotlin => Language::Syntax(Grammar::Kotlin),
+        }
+    }
+}
+
+impl fmt::Display for FileType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl Serialize for FileType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl FromStr for FileType {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, String> {
+        let name = name.trim();
+        FileType::ALL
+            .into_iter()
+            .find(|file_type| file_type.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                let names: Vec<_> = FileType::ALL.iter().map(|t| t.name()).collect();
+                format!(
+                    "Unknown file type '{}', expected one of: {}",
+                    name,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Splits a `PATTERN=TYPE` rule.
+pub(crate) fn parse_rule(rule: &str) -> Result<(String, FileType), String> {
+    let (pattern, name) = rule
+        .rsplit_once('=')
+        .ok_or_else(|| format!("Expected PATTERN=TYPE, got '{}'", rule))?;
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return Err(format!("Missing pattern in '{}'", rule));
+    }
+    Ok((pattern.to_string(), name.parse()?))
+}
+
+/// The user's rules compiled against the scan root, plus the built-in mapping.
+pub(crate) struct FileTypeMap {
+    /// Checked last to first, so later rules win.
+    rules: Vec<(Override, FileType)>,
+    skip_declarations: bool,
+}
+
+impl FileTypeMap {
+    pub(crate) fn new(
+        root: &Path,
+        rules: &[(String, FileType)],
+        skip_declarations: bool,
+    ) -> Result<Self, String> {
+        let rules = rules
+            .iter()
+            .map(|(pattern, file_type)| {
+                let mut builder = OverrideBuilder::new(root);
+                builder.add(&glob_for(pattern)).map_err(|e| e.to_string())?;
+                let matcher = builder.build().map_err(|e| e.to_string())?;
+                Ok((matcher, *file_type))
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self {
+            rules,
+            skip_declarations,
+        })
+    }
+
+    /// Whether the file is a declaration file left out by
+    /// [`ScanOptions::skip_declarations`].
+    ///
+    /// [`ScanOptions::skip_declarations`]: super::ScanOptions::skip_declarations
+    pub(crate) fn skips_declaration(&self, file_path: &Path) -> bool {
+        self.skip_declarations && is_declaration(file_path)
+    }
+
+    /// Picks the file type for a file, or `None` if it isn't scanned.
+    /// `has_locale` tells whether the file was given or looks like it has a
+    /// locale, which makes JSON and YAML files locale resources.
+    pub(crate) fn file_type(&self, file_path: &Path, has_locale: bool) -> Option<FileType> {
+        self.rules
+            .iter()
+            .rev()
+            .find(|(matcher, _)| matcher.matched(file_path, false).is_whitelist())
+            .map(|(_, file_type)| *file_type)
+            .or_else(|| built_in(file_path, has_locale))
+    }
+}
+
+/// An extension written as `.es6` matches `*.es6`; anything else is a glob.
+pub(crate) fn glob_for(pattern: &str) -> String {
+    match pattern.strip_prefix('.') {
+        Some(extension) if !extension.contains(['.', '/', '*', '?', '[', '{']) => {
+            format!("*.{}", extension)
+        }
+        _ => pattern.to_string(),
+    }
+}
+
+fn is_declaration(file_path: &Path) -> bool {
+    file_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| {
+            [".d.ts", ".d.mts", ".d.cts"]
+                .iter()
+                .any(|s| name.ends_with(s))
+        })
+}
+
+fn built_in(file_path: &Path, has_locale: bool) -> Option<FileType> {
+    let Some(extension) = file_path.extension() else {
+        return shebang_file_type(file_path);
+    };
+    let file_type = match extension.to_str()? {
+        "js" => FileType::JavaScript,
+        // SJS is an ES module.
+        "mjs" | "sjs" => FileType::JavaScriptModule,
+        // WXS is CommonJS.
+        "cjs" | "wxs" => FileType::CommonJs,
+        "jsx" => FileType::Jsx,
+        "ts" | "mts" | "cts" => FileType::TypeScript,
+        "tsx" => FileType::Tsx,
+        "vue" => FileType::Vue,
+        "svelte" => FileType::Svelte,
+        "astro" => FileType::Astro,
+        "wxml" | "axml" => FileType::MiniProgramTemplate,
+        "json" if miniprogram::is_config(file_path) => FileType::MiniProgramConfig,
+        "json" if has_locale => FileType::Json,
+        "yaml" | "yml" if has_locale => FileType::Yaml,
+        "properties" => FileType::Properties,
+        "po" => FileType::Po,
+        "xml" if is_android_values(file_path) => FileType::AndroidStrings,
+        "strings" => FileType::AppleStrings,
+        "stringsdict" => FileType::StringsDict,
+        "xcstrings" => FileType::StringCatalog,
+        "arb" => FileType::Arb,
+        "java" => FileType::Java,
+        "go" => FileType::Go,
+        "py" | "pyi" => FileType::Python,
+        "kt" | "kts" => FileType::Kotlin,
+        "html" | "htm" => FileType::Html,
+        "ejs" => FileType::Ejs,
+        "hbs" | "handlebars" => FileType::Handlebars,
+        "pug" | "jade" => FileType::Pug,
+        _ => return None,
+    };
+    Some(file_type)
+}
+
+/// Whether the file is in an Android `values` or `values-<qualifiers>`
+/// resource directory.
+fn is_android_values(file_path: &Path) -> bool {
+    file_path
+        .parent()
+        .and_then(|dir| dir.file_name())
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name == "values" || name.starts_with("values-"))
+}
+
+/// Without an extension, only scripts with a `#!` line for a JavaScript
+/// runtime or Python are scanned: `#!/usr/bin/env node`,
+/// `#!/usr/bin/env -S deno run`, `#!/usr/bin/python3`.
+fn shebang_file_type(file_path: &Path) -> Option<FileType> {
+    let mut head = [0; 128];
+    let read = File::open(file_path)
+        .and_then(|mut file| file.read(&mut head))
+        .ok()?;
+    let line = head[..read].strip_prefix(b"#!")?;
+    let line = &line[..line.iter().position(|&b| b == b'\n').unwrap_or(line.len())];
+    let line = std::str::from_utf8(line).ok()?;
+
+    let interpreter = line
+        .split_whitespace()
+        .map(|word| word.rsplit('/').next().unwrap_or(word))
+        .find(|word| *word != "env" && !word.starts_with('-'))?;
+    match interpreter {
+        "node" | "nodejs" | "bun" => Some(FileType::CommonJs),
+        "deno" | "ts-node" | "tsx" => Some(FileType::TypeScript),
+        python if python.starts_with("python") => Some(FileType::Python),
+        _ => None,
+    }
+}
@@ -0,0 +1,319 @@
+//! Plain HTML and the server-side templates that are HTML with template tags:
+//! EJS (`<% %>`) and Handlebars (`{{ }}`).
+//!
+//! Text nodes and user-visible attributes (`title`, `alt`, `placeholder`,
+//! `aria-label`, `value`) are checked as text. Inline `<script>` blocks go
+//! through the oxc visitor; `<style>` is skipped.
+//!
+//! Template tags are scanned in a separate pass over the whole file, since they
+//! may appear anywhere, including inside a script or a comment. EJS tags are
+//! JavaScript: they are stitched into one program with everything else blanked
+//! out, so `<% if (user) { %> ... <% } %>` parses and offsets stay those of the
+//! file. Handlebars tags aren't JavaScript, so only their string literals, such
+//! as the argument of `{{t "保存"}}`, are parsed.
+
+use super::fallback;
+use super::file::{FileContext, FileScan};
+use super::js;
+use super::markup::{attribute_value, split_interpolations, Segment, Token, Tokenizer};
+use oxc::allocator::Allocator;
+use oxc::span::SourceType;
+
+/// Attributes whose value is shown to the user.
+pub(crate) const VISIBLE_ATTRIBUTES: &[&str] =
+    &["title", "alt", "placeholder", "aria-label", "value"];
+
+#[derive(Clone, Copy)]
+enum Engine {
+    Ejs,
+    Handlebars,
+}
+
+impl Engine {
+    fn delimiters(self) -> (&'static str, &'static str) {
+        match self {
+            Engine::Ejs => ("<%", "%>"),
+            Engine::Handlebars => ("{{", "}}"),
+        }
+    }
+}
+
+pub(crate) fn scan_html(ctx: &FileContext, allocator: &Allocator) -> FileScan {
+    let mut out = FileScan::default();
+    scan_document(ctx, allocator, None, &mut out);
+    out
+}
+
+pub(crate) fn scan_ejs(ctx: &FileContext, allocator: &Allocator) -> FileScan {
+    let mut out = FileScan::default();
+    scan_document(ctx, allocator, Some(Engine::Ejs), &mut out);
+    if let Some(program) = ejs_program(ctx.source_text) {
+        out.extend(js::scan_script(
+            ctx,
+            allocator,
+            &program,
+            0,
+            SourceType::default(),
+        ));
+    }
+    out
+}
+
+pub(crate) fn scan_handlebars(ctx: &FileContext, allocator: &Allocator) -> FileScan {
+    let mut out = FileScan::default();
+    scan_document(ctx, allocator, Some(Engine::Handlebars), &mut out);
+    scan_mustaches(ctx, allocator, &mut out);
+    out
+}
+
+/// Scans the markup. Template tags are left out of text and attribute values;
+/// they are scanned by the engine's own pass.
+fn scan_document(
+    ctx: &FileContext,
+    allocator: &Allocator,
+    engine: Option<Engine>,
+    out: &mut FileScan,
+) {
+    let source = ctx.source_text;
+    let mut tokenizer = Tokenizer::new(source, 0, source.len());
+    if let Some(engine) = engine {
+        let (open, close) = engine.delimiters();
+        tokenizer = tokenizer.with_interpolation(open, close);
+    }
+
+    while let Some(token) = tokenizer.next() {
+        match token {
+            Token::StartTag {
+                name,
+                attributes,
+                self_closing: false,
+            } if name.eq_ignore_ascii_case("script") => {
+                let (code, start) = tokenizer.raw_text("script");
+                let script_type = attribute_value(&attributes, "type").map(|value| value.text);
+                let Some(source_type) = script_source_type(script_type) else {
+                    // JSON data, client-side templates and the like.
+                    continue;
+                };
+                match engine {
+                    Some(engine) if code.contains(engine.delimiters().0) => {
+                        // Not valid JavaScript until the template is rendered.
+                        let masked = mask_tags(code, engine);
+                        out.results
+                            .extend(fallback::scan_tokens(ctx, &masked, start as u32));
+                    }
+                    _ => out.extend(js::scan_script(
+                        ctx,
+                        allocator,
+                        code,
+                        start as u32,
+                        source_type,
+                    )),
+                }
+            }
+            Token::StartTag {
+                name,
+                self_closing: false,
+                ..
+            } if name.eq_ignore_ascii_case("style") => {
+                tokenizer.raw_text("style");
+            }
+            Token::StartTag { attributes, .. } => {
+                for attribute in &attributes {
+                    let visible = VISIBLE_ATTRIBUTES
+                        .iter()
+                        .any(|name| attribute.name.eq_ignore_ascii_case(name));
+                    if let (true, Some(value)) = (visible, &attribute.value) {
+                        check_static(
+                            ctx,
+                            value.text,
+                            value.start,
+                            Some(attribute.name),
+                            engine,
+                            out,
+                        );
+                    }
+                }
+            }
+            Token::Text { text, start } => check_static(ctx, text, start, None, engine, out),
+            Token::EndTag { .. } | Token::Comment => {}
+        }
+    }
+}
+
+/// Checks the parts of a text node, or of the value of `attribute`, outside
+/// template tags.
+fn check_static(
+    ctx: &FileContext,
+    text: &str,
+    start: usize,
+    attribute: Option<&str>,
+    engine: Option<Engine>,
+    out: &mut FileScan,
+) {
+    let Some(engine) = engine else {
+        ctx.check_markup(text, start as u32, attribute, out);
+        return;
+    };
+    let (open, close) = engine.delimiters();
+    for segment in split_interpolations(text, start, open, close) {
+        if let Segment::Static { text, start } = segment {
+            ctx.check_markup(text, start as u32, attribute, out);
+        }
+    }
+}
+
+/// Source type for a `<script type="...">`, or `None` if it isn't JavaScript.
+fn script_source_type(script_type: Option<&str>) -> Option<SourceType> {
+    match script_type
+        .map(|value| value.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        None | Some("" | "text/javascript" | "application/javascript") => {
+            Some(SourceType::default())
+        }
+        Some("module") => Some(SourceType::default().with_module(true)),
+        Some("text/babel" | "text/jsx") => {
+            Some(SourceType::default().with_module(true).with_jsx(true))
+        }
+        _ => None,
+    }
+}
+
+/// Replaces template tags in `code` with spaces, keeping offsets and newlines.
+fn mask_tags(code: &str, engine: Engine) -> String {
+    let (open, close) = engine.delimiters();
+    let mut masked = String::with_capacity(code.len());
+    let mut pos = 0;
+    while let Some(found) = code[pos..].find(open) {
+        let open_at = pos + found;
+        let end = code[open_at + open.len()..]
+            .find(close)
+            .map_or(code.len(), |p| open_at + open.len() + p + close.len());
+        masked.push_str(&code[pos..open_at]);
+        masked.extend(blank(&code[open_at..end]));
+        pos = end;
+    }
+    masked.push_str(&code[pos..]);
+    masked
+}
+
+/// Spaces for every byte of `text`, newlines kept.
+fn blank(text: &str) -> impl Iterator<Item = char> + '_ {
+    text.bytes().map(|b| {
+        if b == b'\n' || b == b'\r' {
+            b as char
+        } else {
+            ' '
+        }
+    })
+}
+
+/// Builds a program from the code in the EJS tags of `source`, with every
+/// other byte blanked so offsets in it are offsets in the file. `<%= x %>` and
+/// `<%- x %>` become the statement `(x);`, scriptlets are kept as they are and
+/// `<%# %>` comments are dropped. Returns `None` if there are no tags.
+fn ejs_program(source: &str) -> Option<String> {
+    let mut program: Vec<u8> = blank(source).map(|c| c as u8).collect();
+    let bytes = source.as_bytes();
+    let mut found_any = false;
+    let mut pos = 0;
+
+    while let Some(found) = source[pos..].find("<%") {
+        let open_at = pos + found;
+        let marker = bytes.get(open_at + 2).copied();
+        if marker == Some(b'%') {
+            // `<%%` is a literal `<%`.
+            pos = open_at + 3;
+            continue;
+        }
+        let code_start = match marker {
+            Some(b'=' | b'-' | b'_' | b'#') => open_at + 3,
+            _ => open_at + 2,
+        };
+        let Some(close) = source[code_start..].find("%>").map(|p| code_start + p) else {
+            break;
+        };
+        pos = close + 2;
+        if marker == Some(b'#') {
+            continue;
+        }
+        // `-%>` and `_%>` trim whitespace; the marker isn't code.
+        let code_end = if close > code_start && matches!(bytes[close - 1], b'-' | b'_') {
+            close - 1
+        } else {
+            close
+        };
+        found_any = true;
+        program[code_start..code_end].copy_from_slice(&bytes[code_start..code_end]);
+        // `;` first so the code can't continue the previous tag's statement.
+        program[open_at] = b';';
+        if matches!(marker, Some(b'=' | b'-')) {
+            program[open_at + 2] = b'(';
+            program[close] = b')';
+            program[close + 1] = b';';
+        } else {
+            program[close] = b';';
+        }
+    }
+
+    // Only ASCII was written over whole characters, so this stays UTF-8.
+    found_any.then(|| String::from_utf8(program).unwrap_or_default())
+}
+
+/// Scans the string literals in every Handlebars tag of the file.
+fn scan_mustaches(ctx: &FileContext, allocator: &Allocator, out: &mut FileScan) {
+    let source = ctx.source_text;
+    let bytes = source.as_bytes();
+    let mut pos = 0;
+
+    while let Some(found) = source[pos..].find("{{") {
+        let open_at = pos + found;
+        if open_at > 0 && bytes[open_at - 1] == b'\\' {
+            // `\{{` is output literally.
+            pos = open_at + 2;
+            continue;
+        }
+        let inner_start = open_at + 2;
+        if source[inner_start..].starts_with('!') {
+            // `{{! comment }}` or `{{!-- comment --}}`
+            let close = if source[inner_start..].starts_with("!--") {
+                "--}}"
+            } else {
+                "}}"
+            };
+            pos = source[inner_start..]
+                .find(close)
+                .map_or(source.len(), |p| inner_start + p + close.len());
+            continue;
+        }
+        let inner_end = source[inner_start..]
+            .find("}}")
+            .map_or(source.len(), |p| inner_start + p);
+        pos = (inner_end + 2).min(source.len());
+
+        let mut i = inner_start;
+        while i < inner_end {
+            let quote = bytes[i];
+            if quote != b'"' && quote != b'\'' {
+                i += 1;
+                continue;
+            }
+            let literal_start = i;
+            i += 1;
+            while i < inner_end && bytes[i] != quote {
+                if bytes[i] == b'\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i = (i + 1).min(inner_end);
+            out.extend(js::scan_expression(
+                ctx,
+                allocator,
+                &source[literal_start..i],
+                literal_start as u32,
+                SourceType::default(),
+            ));
+        }
+    }
+}
@@ -0,0 +1,750 @@
+use super::fallback;
+use super::file::{FileContext, FileScan};
+use super::{Confidence, DiagnosticKind, FindingContext, FindingKind, ScanResult};
+use oxc::allocator::Allocator;
+use oxc::ast::ast::{
+    Argument, ArrowFunctionExpression, AssignmentExpression, AssignmentTarget, BindingIdentifier,
+    BindingPatternKind, BindingProperty, CallExpression, ChainElement, Class, Expression, Function,
+    ImportDefaultSpecifier, ImportNamespaceSpecifier, ImportSpecifier, JSXAttribute,
+    JSXAttributeName, JSXAttributeValue, JSXExpression, JSXExpressionContainer, JSXText,
+    MethodDefinition, ModuleExportName, NewExpression, ObjectProperty, PropertyDefinition,
+    PropertyKey, SimpleAssignmentTarget, StringLiteral, TSEnumMember, TSEnumMemberName,
+    TaggedTemplateExpression, TemplateLiteral, VariableDeclarator,
+};
+use oxc::ast::visit::walk;
+use oxc::ast::{Trivias, Visit};
+use oxc::parser::Parser;
+use oxc::span::{GetSpan, SourceType, Span};
+use oxc::syntax::operator::BinaryOperator;
+use oxc::syntax::scope::ScopeFlags;
+
+pub(crate) struct ChineseVisitor<'c> {
+    pub(crate) results: Vec<ScanResult>,
+    pub(crate) ctx: &'c FileContext<'c>,
+    /// The parsed code, which spans index into.
+    code: &'c str,
+    /// Where the parsed code starts in the file; added to every span.
+    pub(crate) base_offset: u32,
+    /// Names of the named functions the visitor is inside, innermost last.
+    functions: Vec<String>,
+    /// Name of the class whose body is being visited.
+    class: Option<String>,
+    /// Name for the function expression about to be visited, from the
+    /// variable, property or method it is assigned to.
+    function_name: Option<Target>,
+    /// The callee of the call whose arguments are being visited.
+    callee: Option<Target>,
+    /// The attribute or property whose value is being visited.
+    property: Option<Target>,
+}
+
+#[derive(Clone, Copy)]
+enum Slot {
+    FunctionName,
+    Callee,
+    Property,
+}
+
+/// A name that applies to the nodes with the given spans.
+struct Target {
+    spans: Vec<Span>,
+    name: String,
+}
+
+impl Target {
+    /// A target for the strings `expr` evaluates to, see [`value_spans`].
+    fn value(expr: &Expression, name: impl Into<String>) -> Self {
+        let mut spans = Vec::new();
+        value_spans(expr, &mut spans);
+        Self {
+            spans,
+            name: name.into(),
+        }
+    }
+
+    fn name_for(target: &Option<Self>, span: Span) -> Option<String> {
+        target
+            .as_ref()
+            .filter(|target| target.spans.contains(&span))
+            .map(|target| target.name.clone())
+    }
+}
+
+impl<'c> ChineseVisitor<'c> {
+    pub(crate) fn new(ctx: &'c FileContext<'c>, code: &'c str, base_offset: u32) -> Self {
+        Self {
+            results: Vec::new(),
+            ctx,
+            code,
+            base_offset,
+            functions: Vec::new(),
+            class: None,
+            function_name: None,
+            callee: None,
+            property: None,
+        }
+    }
+
+    /// Reports a name starting at `start` in the parsed code, when names are checked.
+    fn check_name(&mut self, name: &str, start: u32, kind: FindingKind) {
+        if self.ctx.identifiers {
+            self.ctx
+                .check_name(name, self.base_offset + start, kind, &mut self.results);
+        }
+    }
+
+    /// Reports `text` if `cooked`, decoded from the code at `raw`, contains
+    /// Chinese, with the context of the node at `span`.
+    fn report(
+        &mut self,
+        cooked: &str,
+        raw: Span,
+        escapes: bool,
+        text: &str,
+        kind: FindingKind,
+        span: Span,
+    ) {
+        let raw_text = &self.code[raw.start as usize..raw.end as usize];
+        let offset = (self.base_offset + raw.start) as usize;
+        let segments = self.ctx.raw_segments(cooked, raw_text, offset, escapes);
+        if segments.is_empty() {
+            return;
+        }
+        let mut result = self.ctx.result(segments, text, Confidence::High, kind);
+        result.context = FindingContext {
+            function: self.functions.last().cloned(),
+            callee: Target::name_for(&self.callee, span),
+            property: Target::name_for(&self.property, span),
+        };
+        self.results.push(result);
+    }
+
+    /// Visits a function body, under `name` if it has one.
+    fn with_function(&mut self, name: Option<String>, visit: impl FnOnce(&mut Self)) {
+        let named = name.is_some();
+        self.functions.extend(name);
+        visit(self);
+        if named {
+            self.functions.pop();
+        }
+    }
+
+    fn slot(&mut self, slot: Slot) -> &mut Option<Target> {
+        match slot {
+            Slot::FunctionName => &mut self.function_name,
+            Slot::Callee => &mut self.callee,
+            Slot::Property => &mut self.property,
+        }
+    }
+
+    /// Visits with `slot` set to `target`, restoring the outer one afterwards.
+    fn with_target(&mut self, slot: Slot, target: Target, visit: impl FnOnce(&mut Self)) {
+        let outer = self.slot(slot).replace(target);
+        visit(self);
+        *self.slot(slot) = outer;
+    }
+
+    /// Visits with `property` naming the strings `value` evaluates to, and
+    /// `function` naming it if it is a function expression.
+    fn with_value_target(
+        &mut self,
+        property: &str,
+        function: &str,
+        value: &Expression,
+        visit: impl FnOnce(&mut Self),
+    ) {
+        let function = function_span(value).map(|span| Target {
+            spans: vec![span],
+            name: function.to_string(),
+        });
+        self.with_target(
+            Slot::Property,
+            Target::value(value, property),
+            |v| match function {
+                Some(function) => v.with_target(Slot::FunctionName, function, visit),
+                None => visit(v),
+            },
+        );
+    }
+
+    /// Name for a method or class field: `Class.name` inside a named class.
+    fn member_name(&self, key: &PropertyKey) -> Option<String> {
+        let name = key.name()?;
+        Some(match &self.class {
+            Some(class) => format!("{}.{}", class, name),
+            None => name.to_string(),
+        })
+    }
+}
+
+/// Collects the spans of the string and template literals `expr` evaluates
+/// to, looking through conditionals, `||`/`??`, `+` and type assertions, so
+/// `t(ok ? '成功' : '失败')` gives both strings the callee `t`.
+fn value_spans(expr: &Expression, spans: &mut Vec<Span>) {
+    match expr {
+        Expression::StringLiteral(lit) => spans.push(lit.span),
+        Expression::TemplateLiteral(lit) => spans.push(lit.span),
+        Expression::ConditionalExpression(expr) => {
+            value_spans(&expr.consequent, spans);
+            value_spans(&expr.alternate, spans);
+        }
+        Expression::LogicalExpression(expr) => {
+            value_spans(&expr.left, spans);
+            value_spans(&expr.right, spans);
+        }
+        Expression::BinaryExpression(expr) if expr.operator == BinaryOperator::Addition => {
+            value_spans(&expr.left, spans);
+            value_spans(&expr.right, spans);
+        }
+        Expression::ParenthesizedExpression(expr) => value_spans(&expr.expression, spans),
+        Expression::TSAsExpression(expr) => value_spans(&expr.expression, spans),
+        Expression::TSSatisfiesExpression(expr) => value_spans(&expr.expression, spans),
+        Expression::TSNonNullExpression(expr) => value_spans(&expr.expression, spans),
+        _ => {}
+    }
+}
+
+/// The span of a function expression, for naming it after what it's assigned to.
+fn function_span(expr: &Expression) -> Option<Span> {
+    match expr {
+        Expression::FunctionExpression(func) => Some(func.span),
+        Expression::ArrowFunctionExpression(func) => Some(func.span),
+        _ => None,
+    }
+}
+
+/// `console.log`, `this.$t` or `t`; `None` for callees that aren't a plain
+/// chain of names, such as `getI18n().t` or an IIFE.
+fn callee_name(callee: &Expression) -> Option<String> {
+    match callee {
+        Expression::Identifier(ident) => Some(ident.name.to_string()),
+        Expression::ThisExpression(_) => Some("this".to_string()),
+        Expression::Super(_) => Some("super".to_string()),
+        Expression::MemberExpression(member) => Some(format!(
+            "{}.{}",
+            callee_name(member.object())?,
+            member.static_property_name()?
+        )),
+        Expression::ChainExpression(chain) => match &chain.expression {
+            ChainElement::MemberExpression(member) => Some(format!(
+                "{}.{}",
+                callee_name(member.object())?,
+                member.static_property_name()?
+            )),
+            ChainElement::CallExpression(_) => None,
+        },
+        Expression::TSNonNullExpression(expr) => callee_name(&expr.expression),
+        _ => None,
+    }
+}
+
+/// The span of a quoted string's content, without the quotes.
+fn quoted(span: Span) -> Span {
+    Span::new(
+        span.start + 1,
+        span.end.saturating_sub(1).max(span.start + 1),
+    )
+}
+
+/// A target giving the strings passed as `arguments` the callee's name.
+fn callee_target(callee: &Expression, arguments: &[Argument]) -> Option<Target> {
+    let name = callee_name(callee)?;
+    let mut spans = Vec::new();
+    for argument in arguments {
+        if let Argument::Expression(expr) = argument {
+            value_spans(expr, &mut spans);
+        }
+    }
+    Some(Target { spans, name })
+}
+
+fn jsx_attribute_name(name: &JSXAttributeName) -> String {
+    match name {
+        JSXAttributeName::Identifier(ident) => ident.name.to_string(),
+        JSXAttributeName::NamespacedName(name) => {
+            format!("{}:{}", name.namespace.name, name.property.name)
+        }
+    }
+}
+
+impl<'a> Visit<'a> for ChineseVisitor<'_> {
+    fn visit_string_literal(&mut self, lit: &StringLiteral<'a>) {
+        let kind = FindingKind::StringLiteral;
+        self.report(
+            &lit.value,
+            quoted(lit.span),
+            true,
+            &lit.value,
+            kind,
+            lit.span,
+        );
+    }
+
+    fn visit_template_literal(&mut self, lit: &TemplateLiteral<'a>) {
+        for part in &lit.quasis {
+            if let Some(cooked) = &part.value.cooked {
+                let kind = FindingKind::TemplateLiteral;
+                self.report(cooked, part.span, true, cooked, kind, lit.span);
+            }
+        }
+        walk::walk_template_literal(self, lit);
+    }
+
+    fn visit_jsx_text(&mut self, text: &JSXText<'a>) {
+        // JSX has no escapes; the range is computed on the whole text, so
+        // trimming the reported text doesn't shift it.
+        let trimmed_value = text.value.trim();
+        let kind = FindingKind::JsxText;
+        self.report(
+            &text.value,
+            text.span,
+            false,
+            trimmed_value,
+            kind,
+            text.span,
+        );
+    }
+
+    fn visit_jsx_attribute(&mut self, attribute: &JSXAttribute<'a>) {
+        let name = jsx_attribute_name(&attribute.name);
+        match &attribute.value {
+            Some(JSXAttributeValue::StringLiteral(lit)) => {
+                let target = Target {
+                    spans: vec![lit.span],
+                    name,
+                };
+                let kind = FindingKind::JsxAttribute;
+                self.with_target(Slot::Property, target, |v| {
+                    v.report(
+                        &lit.value,
+                        quoted(lit.span),
+                        false,
+                        &lit.value,
+                        kind,
+                        lit.span,
+                    )
+                });
+            }
+            Some(JSXAttributeValue::ExpressionContainer(JSXExpressionContainer {
+                expression: JSXExpression::Expression(expr),
+                ..
+            })) => {
+                self.with_target(Slot::Property, Target::value(expr, name), |v| {
+                    walk::walk_jsx_attribute(v, attribute)
+                });
+            }
+            _ => walk::walk_jsx_attribute(self, attribute),
+        }
+    }
+
+    fn visit_binding_identifier(&mut self, ident: &BindingIdentifier<'a>) {
+        self.check_name(&ident.name, ident.span.start, FindingKind::Identifier);
+    }
+
+    fn visit_property_key(&mut self, key: &PropertyKey<'a>) {
+        match key {
+            PropertyKey::Identifier(ident) => {
+                self.check_name(&ident.name, ident.span.start, FindingKind::PropertyKey);
+            }
+            // The span includes the `#`.
+            PropertyKey::PrivateIdentifier(ident) => {
+                self.check_name(&ident.name, ident.span.start + 1, FindingKind::PropertyKey);
+            }
+            PropertyKey::Expression(_) => walk::walk_property_key(self, key),
+        }
+    }
+
+    fn visit_binding_property(&mut self, prop: &BindingProperty<'a>) {
+        // `const { 状态 } = props` names the key and the binding at once;
+        // report it once, as the identifier it declares.
+        if !prop.shorthand {
+            self.visit_property_key(&prop.key);
+        }
+        self.visit_binding_pattern(&prop.value);
+    }
+
+    fn visit_enum_member(&mut self, member: &TSEnumMember<'a>) {
+        let name = match &member.id {
+            TSEnumMemberName::Identifier(ident) => {
+                self.check_name(&ident.name, ident.span.start, FindingKind::PropertyKey);
+                Some(&ident.name)
+            }
+            TSEnumMemberName::StringLiteral(lit) => Some(&lit.value),
+            _ => None,
+        };
+        match (name, &member.initializer) {
+            (Some(name), Some(initializer)) => {
+                let target = Target::value(initializer, name.as_str());
+                self.with_target(Slot::Property, target, |v| {
+                    walk::walk_enum_member(v, member)
+                });
+            }
+            _ => walk::walk_enum_member(self, member),
+        }
+    }
+
+    fn visit_function(&mut self, func: &Function<'a>, flags: Option<ScopeFlags>) {
+        let name = match &func.id {
+            Some(id) => Some(id.name.to_string()),
+            None => Target::name_for(&self.function_name, func.span),
+        };
+        self.with_function(name, |v| walk::walk_function(v, func, flags));
+    }
+
+    fn visit_arrow_expression(&mut self, expr: &ArrowFunctionExpression<'a>) {
+        let name = Target::name_for(&self.function_name, expr.span);
+        self.with_function(name, |v| walk::walk_arrow_expression(v, expr));
+    }
+
+    fn visit_class(&mut self, class: &Class<'a>) {
+        let name = class.id.as_ref().map(|id| id.name.to_string());
+        let outer = std::mem::replace(&mut self.class, name);
+        walk::walk_class(self, class);
+        self.class = outer;
+    }
+
+    fn visit_variable_declarator(&mut self, declarator: &VariableDeclarator<'a>) {
+        // `const Page = () => {}` names the component `Page`.
+        let function = match (&declarator.id.kind, &declarator.init) {
+            (BindingPatternKind::BindingIdentifier(id), Some(init)) => {
+                function_span(init).map(|span| Target {
+                    spans: vec![span],
+                    name: id.name.to_string(),
+                })
+            }
+            _ => None,
+        };
+        match function {
+            Some(target) => self.with_target(Slot::FunctionName, target, |v| {
+                walk::walk_variable_declarator(v, declarator)
+            }),
+            None => walk::walk_variable_declarator(self, declarator),
+        }
+    }
+
+    fn visit_object_property(&mut self, prop: &ObjectProperty<'a>) {
+        match prop.key.static_name() {
+            Some(name) => self.with_value_target(name.as_str(), name.as_str(), &prop.value, |v| {
+                walk::walk_object_property(v, prop)
+            }),
+            None => walk::walk_object_property(self, prop),
+        }
+    }
+
+    fn visit_method_definition(&mut self, def: &MethodDefinition<'a>) {
+        match self.member_name(&def.key) {
+            Some(name) => {
+                let target = Target {
+                    spans: vec![def.value.span],
+                    name,
+                };
+                self.with_target(Slot::FunctionName, target, |v| {
+                    walk::walk_method_definition(v, def)
+                });
+            }
+            None => walk::walk_method_definition(self, def),
+        }
+    }
+
+    fn visit_property_definition(&mut self, def: &PropertyDefinition<'a>) {
+        match (def.key.name(), self.member_name(&def.key), &def.value) {
+            (Some(name), Some(member), Some(value)) => {
+                self.with_value_target(name.as_str(), &member, value, |v| {
+                    walk::walk_property_definition(v, def)
+                });
+            }
+            _ => walk::walk_property_definition(self, def),
+        }
+    }
+
+    fn visit_assignment_expression(&mut self, expr: &AssignmentExpression<'a>) {
+        // `this.title = '标题'` or `exports.handler = function () {}`.
+        let name = match &expr.left {
+            AssignmentTarget::SimpleAssignmentTarget(
+                SimpleAssignmentTarget::MemberAssignmentTarget(member),
+            ) => member.static_property_name(),
+            _ => None,
+        };
+        match name {
+            Some(name) => self.with_value_target(name, name, &expr.right, |v| {
+                walk::walk_assignment_expression(v, expr)
+            }),
+            None => walk::walk_assignment_expression(self, expr),
+        }
+    }
+
+    fn visit_call_expression(&mut self, expr: &CallExpression<'a>) {
+        match callee_target(&expr.callee, &expr.arguments) {
+            Some(target) => self.with_target(Slot::Callee, target, |v| {
+                walk::walk_call_expression(v, expr)
+            }),
+            None => walk::walk_call_expression(self, expr),
+        }
+    }
+
+    fn visit_new_expression(&mut self, expr: &NewExpression<'a>) {
+        match callee_target(&expr.callee, &expr.arguments) {
+            Some(target) => {
+                self.with_target(Slot::Callee, target, |v| walk::walk_new_expression(v, expr))
+            }
+            None => walk::walk_new_expression(self, expr),
+        }
+    }
+
+    fn visit_tagged_template_expression(&mut self, expr: &TaggedTemplateExpression<'a>) {
+        match callee_name(&expr.tag) {
+            Some(name) => {
+                let target = Target {
+                    spans: vec![expr.quasi.span],
+                    name,
+                };
+                self.with_target(Slot::Callee, target, |v| {
+                    walk::walk_tagged_template_expression(v, expr)
+                });
+            }
+            None => walk::walk_tagged_template_expression(self, expr),
+        }
+    }
+
+    fn visit_import_specifier(&mut self, specifier: &ImportSpecifier<'a>) {
+        // `import { 用户 }` has a single name; `import { 用户 as user }` has two.
+        if specifier.imported.span() != specifier.local.span {
+            match &specifier.imported {
+                ModuleExportName::Identifier(ident) => {
+                    self.check_name(&ident.name, ident.span.start, FindingKind::ImportSpecifier);
+                }
+                // +1 for the opening quote.
+                ModuleExportName::StringLiteral(lit) => {
+                    self.check_name(&lit.value, lit.span.start + 1, FindingKind::ImportSpecifier);
+                }
+            }
+        }
+        let local = &specifier.local;
+        self.check_name(&local.name, local.span.start, FindingKind::ImportSpecifier);
+    }
+
+    fn visit_import_default_specifier(&mut self, specifier: &ImportDefaultSpecifier<'a>) {
+        let local = &specifier.local;
+        self.check_name(&local.name, local.span.start, FindingKind::ImportSpecifier);
+    }
+
+    fn visit_import_name_specifier(&mut self, specifier: &ImportNamespaceSpecifier<'a>) {
+        let local = &specifier.local;
+        self.check_name(&local.name, local.span.start, FindingKind::ImportSpecifier);
+    }
+}
+
+/// Parses `code`, which starts at `base_offset` in the file, and collects its
+/// findings plus one diagnostic per syntax error. Whatever oxc recovers is
+/// still visited; if it gives up entirely, the token-level fallback runs.
+pub(crate) fn scan_script(
+    ctx: &FileContext,
+    allocator: &Allocator,
+    code: &str,
+    base_offset: u32,
+    source_type: SourceType,
+) -> FileScan {
+    let parser = Parser::new(allocator, code, source_type);
+    let ret = parser.parse();
+
+    let diagnostics = ret
+        .errors
+        .iter()
+        .map(|error| {
+            let offset = error
+                .labels()
+                .and_then(|mut labels| labels.next())
+                .map(|label| base_offset + label.offset() as u32);
+            ctx.diagnostic(DiagnosticKind::Parse, error.to_string(), offset)
+        })
+        .collect();
+
+    let mut out = if ret.panicked {
+        // oxc gave up and returned an empty program; fall back to a token scan.
+        FileScan {
+            results: fallback::scan_tokens(ctx, code, base_offset),
+            diagnostics,
+        }
+    } else {
+        // Visit whatever oxc recovered, even when it reported errors.
+        let mut visitor = ChineseVisitor::new(ctx, code, base_offset);
+        visitor.visit_program(&ret.program);
+        FileScan {
+            results: visitor.results,
+            diagnostics,
+        }
+    };
+    check_comments(ctx, &ret.trivias, code, base_offset, &mut out);
+    out
+}
+
+/// Reports the line, block and JSX comments oxc collected while lexing `code`
+/// when comments are scanned. Their spans exclude the `//` and `/* */`.
+fn check_comments(
+    ctx: &FileContext,
+    trivias: &Trivias,
+    code: &str,
+    base_offset: u32,
+    out: &mut FileScan,
+) {
+    if !ctx.comments {
+        return;
+    }
+    for (_, span) in trivias.comments() {
+        let text = &code[span.start as usize..span.end as usize];
+        ctx.check_comment(text, base_offset + span.start, out);
+    }
+}
+
+/// Scans a `.js` file, which may be an ES module or a classic script. It is
+/// parsed as a module, so top-level `await` and `import` parse, and again as a
+/// script if that has errors, e.g. on `with` or legacy octal literals; the
+/// parse with fewer errors is kept.
+pub(crate) fn scan_javascript(ctx: &FileContext, allocator: &Allocator, code: &str) -> FileScan {
+    let module = SourceType::default().with_module(true).with_jsx(true);
+    let out = scan_script(ctx, allocator, code, 0, module);
+    if out.diagnostics.is_empty() {
+        return out;
+    }
+    let script = scan_script(ctx, allocator, code, 0, module.with_script(true));
+    if script.diagnostics.len() < out.diagnostics.len() {
+        script
+    } else {
+        out
+    }
+}
+
+/// Scans an embedded expression such as a template interpolation or a bound
+/// attribute value. It is parsed wrapped in parentheses first; code that isn't
+/// a single expression (`a++; b()` in an event handler) is parsed as statements.
+pub(crate) fn scan_expression(
+    ctx: &FileContext,
+    allocator: &Allocator,
+    code: &str,
+    base_offset: u32,
+    source_type: SourceType,
+) -> FileScan {
+    if base_offset > 0 {
+        let wrapped = format!("({})", code);
+        let ret = Parser::new(allocator, &wrapped, source_type).parse();
+        if ret.errors.is_empty() {
+            // The opening parenthesis isn't in the file.
+            let mut visitor = ChineseVisitor::new(ctx, &wrapped, base_offset - 1);
+            visitor.visit_program(&ret.program);
+            let mut out = FileScan {
+                results: visitor.results,
+                diagnostics: Vec::new(),
+            };
+            check_comments(ctx, &ret.trivias, &wrapped, base_offset - 1, &mut out);
+            return out;
+        }
+    }
+    scan_script(ctx, allocator, code, base_offset, source_type)
+}
+
+/// Source type for a `<script lang="...">` block, or `default` without `lang`.
+pub(crate) fn lang_source_type(lang: Option<&str>, default: SourceType) -> SourceType {
+    let module = SourceType::default().with_module(true);
+    match lang {
+        Some("ts" | "typescript") => module.with_typescript(true),
+        Some("tsx") => module.with_typescript(true).with_jsx(true),
+        Some("jsx") => module.with_jsx(true),
+        Some("js" | "javascript") => module,
+        _ => default,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::scan_script;
+    use crate::scanner::file::{covered, scan_test_source};
+    use crate::scanner::testing::TempTree;
+    use crate::scanner::{FindingContext, FindingKind, ScanOptions, Scanner};
+    use oxc::allocator::Allocator;
+    use oxc::span::SourceType;
+
+    fn context(
+        function: Option<&str>,
+        callee: Option<&str>,
+        property: Option<&str>,
+    ) -> FindingContext {
+        FindingContext {
+            function: function.map(String::from),
+            callee: callee.map(String::from),
+            property: property.map(String::from),
+        }
+    }
+
+    #[test]
+    fn findings_carry_their_syntactic_context() {
+        let source = "class Page {\n  title = \"标题\";\n  render() {\n    console.log(`加载${n}完成`);\n    return <Button label=\"确定\">取消</Button>;\n  }\n}\nconst save = () => t(\"保存\", { hint: \"提示\" });\n";
+        let allocator = Allocator::default();
+        let source_type = SourceType::default().with_module(true).with_jsx(true);
+        let results = scan_test_source(source, |ctx| {
+            scan_script(ctx, &allocator, source, 0, source_type)
+        });
+        let found: Vec<_> = results
+            .iter()
+            .map(|result| (covered(source, result), result.kind, &result.context))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    "标题",
+                    FindingKind::StringLiteral,
+                    &context(None, None, Some("title"))
+                ),
+                (
+                    "加载",
+                    FindingKind::TemplateLiteral,
+                    &context(Some("Page.render"), Some("console.log"), None)
+                ),
+                (
+                    "完成",
+                    FindingKind::TemplateLiteral,
+                    &context(Some("Page.render"), Some("console.log"), None)
+                ),
+                (
+                    "确定",
+                    FindingKind::JsxAttribute,
+                    &context(Some("Page.render"), None, Some("label"))
+                ),
+                (
+                    "取消",
+                    FindingKind::JsxText,
+                    &context(Some("Page.render"), None, None)
+                ),
+                (
+                    "保存",
+                    FindingKind::StringLiteral,
+                    &context(Some("save"), Some("t"), None)
+                ),
+                (
+                    "提示",
+                    FindingKind::StringLiteral,
+                    &context(Some("save"), None, Some("hint"))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn js_files_parse_as_modules_or_scripts() {
+        let tree = TempTree::new(&[
+            (
+                "esm.js",
+                "import x from \"y\";\nconst a = await f(\"模块\");\n",
+            ),
+            ("sloppy.js", "with (o) { f(\"脚本\"); }\nvar s = 010;\n"),
+        ]);
+        let report = Scanner::scan(&ScanOptions::new(tree.path())).unwrap();
+        assert!(report.diagnostics.is_empty());
+        let texts: Vec<_> = report
+            .results
+            .iter()
+            .map(|result| result.text.as_str())
+            .collect();
+        assert_eq!(texts, ["模块", "脚本"]);
+    }
+}
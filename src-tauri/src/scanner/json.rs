@@ -0,0 +1,250 @@
+//! A small JSON reader that reports every string value with its position and
+//! key path. serde_json doesn't keep source offsets, which findings need.
+
+use super::file::{FileContext, FileScan};
+use super::key_path::KeyPath;
+use super::DiagnosticKind;
+
+/// A string value found in a JSON document.
+pub(crate) struct JsonString<'s, 'p> {
+    /// The value with escapes decoded.
+    pub(crate) value: String,
+    /// Byte offset of the raw content, just after the opening quote.
+    pub(crate) start: usize,
+    /// The raw content between the quotes.
+    pub(crate) raw: &'s str,
+    /// Where the value is, e.g. `pages[0]` or `window.navigationBarTitleText`.
+    pub(crate) path: &'p KeyPath,
+}
+
+/// Scans every string value in a JSON file.
+pub(crate) fn scan_json(ctx: &FileContext) -> FileScan {
+    let mut out = FileScan::default();
+    let result = walk_strings(ctx.source_text, |string| {
+        let key = string.path.to_string();
+        ctx.check_value(&string.value, string.raw, string.start, &key, &mut out);
+    });
+    if let Err((message, offset)) = result {
+        out.diagnostics
+            .push(ctx.diagnostic(DiagnosticKind::Parse, message, Some(offset as u32)));
+    }
+    out
+}
+
+/// Calls `visit` for every string value in `source`. Keys are not reported.
+/// Stops at the first syntax error and returns its message and offset.
+pub(crate) fn walk_strings(
+    source: &str,
+    mut visit: impl FnMut(JsonString),
+) -> Result<(), (String, usize)> {
+    let mut reader = Reader {
+        source,
+        bytes: source.as_bytes(),
+        pos: 0,
+        path: KeyPath::default(),
+    };
+    // A UTF-8 byte order mark is not part of the document.
+    if source.starts_with('\u{feff}') {
+        reader.pos = 3;
+    }
+    reader.value(&mut visit)?;
+    reader.skip_whitespace();
+    if reader.pos < reader.bytes.len() {
+        return Err(reader.error("Unexpected content after JSON value"));
+    }
+    Ok(())
+}
+
+struct Reader<'s> {
+    source: &'s str,
+    bytes: &'s [u8],
+    pos: usize,
+    path: KeyPath,
+}
+
+impl<'s> Reader<'s> {
+    fn value(&mut self, visit: &mut impl FnMut(JsonString)) -> Result<(), (String, usize)> {
+        self.skip_whitespace();
+        match self.bytes.get(self.pos) {
+            Some(b'{') => self.object(visit),
+            Some(b'[') => self.array(visit),
+            Some(b'"') => {
+                let (value, start, raw) = self.string()?;
+                visit(JsonString {
+                    value,
+                    start,
+                    raw,
+                    path: &self.path,
+                });
+                Ok(())
+            }
+            Some(_) => {
+                // Number, true, false or null.
+                let start = self.pos;
+                while self.pos < self.bytes.len()
+                    && !matches!(self.bytes[self.pos], b',' | b'}' | b']')
+                    && !self.bytes[self.pos].is_ascii_whitespace()
+                {
+                    self.pos += 1;
+                }
+                if self.pos == start {
+                    return Err(self.error("Expected a value"));
+                }
+                Ok(())
+            }
+            None => Err(self.error("Unexpected end of JSON")),
+        }
+    }
+
+    fn object(&mut self, visit: &mut impl FnMut(JsonString)) -> Result<(), (String, usize)> {
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.bytes.get(self.pos) == Some(&b'}') {
+            self.pos += 1;
+            return Ok(());
+        }
+        loop {
+            self.skip_whitespace();
+            if self.bytes.get(self.pos) != Some(&b'"') {
+                return Err(self.error("Expected a string key"));
+            }
+            let (key, _, _) = self.string()?;
+            self.skip_whitespace();
+            if self.bytes.get(self.pos) != Some(&b':') {
+                return Err(self.error("Expected ':'"));
+            }
+            self.pos += 1;
+            self.path.push_key(key);
+            self.value(visit)?;
+            self.path.pop();
+            if self.separator(b'}')? {
+                return Ok(());
+            }
+        }
+    }
+
+    fn array(&mut self, visit: &mut impl FnMut(JsonString)) -> Result<(), (String, usize)> {
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.bytes.get(self.pos) == Some(&b']') {
+            self.pos += 1;
+            return Ok(());
+        }
+        for index in 0.. {
+            self.path.push_index(index);
+            self.value(visit)?;
+            self.path.pop();
+            if self.separator(b']')? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Consumes a `,` or the closing byte; returns `true` for the latter.
+    fn separator(&mut self, close: u8) -> Result<bool, (String, usize)> {
+        self.skip_whitespace();
+        match self.bytes.get(self.pos) {
+            Some(b',') => {
+                self.pos += 1;
+                Ok(false)
+            }
+            Some(&b) if b == close => {
+                self.pos += 1;
+                Ok(true)
+            }
+            _ => Err(self.error(&format!("Expected ',' or '{}'", close as char))),
+        }
+    }
+
+    /// Reads a string at `pos`; returns the decoded value, where the raw content
+    /// starts and the raw content.
+    fn string(&mut self) -> Result<(String, usize, &'s str), (String, usize)> {
+        let start = self.pos + 1;
+        let mut i = start;
+        let mut value = String::new();
+        let mut chunk_start = start;
+        while i < self.bytes.len() {
+            match self.bytes[i] {
+                b'"' => {
+                    value.push_str(&self.source[chunk_start..i]);
+                    self.pos = i + 1;
+                    return Ok((value, start, &self.source[start..i]));
+                }
+                b'\\' => {
+                    value.push_str(&self.source[chunk_start..i]);
+                    let (decoded, len) = self.escape(i)?;
+                    value.push_str(&decoded);
+                    i += len;
+                    chunk_start = i;
+                }
+                _ => i += 1,
+            }
+        }
+        self.pos = i;
+        Err(self.error("Unterminated string"))
+    }
+
+    /// Decodes the escape sequence at `i`; returns it and its length in bytes.
+    fn escape(&self, i: usize) -> Result<(String, usize), (String, usize)> {
+        let decoded = match self.bytes.get(i + 1) {
+            Some(b'"') => '"',
+            Some(b'\\') => '\\',
+            Some(b'/') => '/',
+            Some(b'b') => '\u{8}',
+            Some(b'f') => '\u{c}',
+            Some(b'n') => '\n',
+            Some(b'r') => '\r',
+            Some(b't') => '\t',
+            Some(b'u') => {
+                let high = self.hex4(i + 2)?;
+                if (0xD800..0xDC00).contains(&high) && self.source[i + 6..].starts_with("\\u") {
+                    let low = self.hex4(i + 8)?;
+                    let code =
+                        0x10000 + ((high - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF);
+                    let c = char::from_u32(code).unwrap_or('\u{fffd}');
+                    return Ok((c.to_string(), 12));
+                }
+                let c = char::from_u32(high).unwrap_or('\u{fffd}');
+                return Ok((c.to_string(), 6));
+            }
+            _ => return Err(("Invalid escape sequence".to_string(), i)),
+        };
+        Ok((decoded.to_string(), 2))
+    }
+
+    fn hex4(&self, at: usize) -> Result<u32, (String, usize)> {
+        self.source
+            .get(at..at + 4)
+            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+            .ok_or_else(|| ("Invalid unicode escape".to_string(), at))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.bytes.len() && self.bytes[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn error(&self, message: &str) -> (String, usize) {
+        (message.to_string(), self.pos.min(self.bytes.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::scan_json;
+    use crate::scanner::file::{covered, keyed, scan_test_source};
+
+    #[test]
+    fn values_under_key_paths() {
+        let source = "{\n  \"common\": {\"save\": \"保存\", \"中文键\": \"ok\"},\n  \"list\": [\"第一\", \"\\u4e2d\\u6587\"]\n}\n";
+        let results = scan_test_source(source, scan_json);
+        assert_eq!(results.len(), 3);
+        assert_eq!(covered(source, keyed(&results, "common.save")), "保存");
+        assert_eq!(covered(source, keyed(&results, "list[0]")), "第一");
+        let escaped = keyed(&results, "list[1]");
+        assert_eq!(escaped.text, "中文");
+        assert_eq!(covered(source, escaped), "\\u4e2d\\u6587");
+    }
+}
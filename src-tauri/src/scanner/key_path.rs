@@ -0,0 +1,58 @@
+//! Key paths such as `common.buttons[0].label`, reported for findings in
+//! resource files.
+
+use std::fmt;
+
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Default)]
+pub(crate) struct KeyPath {
+    segments: Vec<Segment>,
+}
+
+impl KeyPath {
+    pub(crate) fn push_key(&mut self, key: impl Into<String>) {
+        self.segments.push(Segment::Key(key.into()));
+    }
+
+    pub(crate) fn push_index(&mut self, index: usize) {
+        self.segments.push(Segment::Index(index));
+    }
+
+    pub(crate) fn pop(&mut self) {
+        self.segments.pop();
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.segments.truncate(len);
+    }
+
+    /// The key at `index`, or `None` if that segment is an array index or
+    /// the path is shorter.
+    pub(crate) fn key(&self, index: usize) -> Option<&str> {
+        match self.segments.get(index)? {
+            Segment::Key(key) => Some(key),
+            Segment::Index(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for KeyPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Key(key) if i == 0 => f.write_str(key)?,
+                Segment::Key(key) => write!(f, ".{}", key)?,
+                Segment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
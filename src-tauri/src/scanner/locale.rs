@@ -0,0 +1,252 @@
+//! Which locale a resource file belongs to.
+//!
+//! Chinese in a Chinese locale's files is expected, so those files aren't
+//! scanned; anywhere else it means a string was never translated. The locale
+//! comes from the user's rules or, failing that, from the path: the file name
+//! (`en.json`, `app.en.yml`, `messages_zh_CN.properties`) or the nearest
+//! directory named like a locale (`locales/en/common.json`,
+//! `locale/fr/LC_MESSAGES/app.po`), including Android `values-zh-rCN` and
+//! Apple `zh-Hans.lproj` folders.
+
+use ignore::overrides::{Override, OverrideBuilder};
+use std::path::Path;
+
+/// ISO 639-1 language codes. Checking against the list keeps directories such
+/// as `js` or `ui` from being taken for locales.
+const LANGUAGES: &[&str] = &[
+    "aa", "ab", "ae", "af", "ak", "am", "an", "ar", "as", "av", "ay", "az", "ba", "be", "bg", "bh",
+    "bi", "bm", "bn", "bo", "br", "bs", "ca", "ce", "ch", "co", "cr", "cs", "cu", "cv", "cy", "da",
+    "de", "dv", "dz", "ee", "el", "en", "eo", "es", "et", "eu", "fa", "ff", "fi", "fj", "fo", "fr",
+    "fy", "ga", "gd", "gl", "gn", "gu", "gv", "ha", "he", "hi", "ho", "hr", "ht", "hu", "hy", "hz",
+    "ia", "id", "ie", "ig", "ii", "ik", "io", "is", "it", "iu", "ja", "jv", "ka", "kg", "ki", "kj",
+    "kk", "kl", "km", "kn", "ko", "kr", "ks", "ku", "kv", "kw", "ky", "la", "lb", "lg", "li", "ln",
+    "lo", "lt", "lu", "lv", "mg", "mh", "mi", "mk", "ml", "mn", "mr", "ms", "mt", "my", "na", "nb",
+    "nd", "ne", "ng", "nl", "nn", "no", "nr", "nv", "ny", "oc", "oj", "om", "or", "os", "pa", "pi",
+    "pl", "ps", "pt", "qu", "rm", "rn", "ro", "ru", "rw", "sa", "sc", "sd", "se", "sg", "si", "sk",
+    "sl", "sm", "sn", "so", "sq", "sr", "ss", "st", "su", "sv", "sw", "ta", "te", "tg", "th", "ti",
+    "tk", "tl", "tn", "to", "tr", "ts", "tt", "tw", "ty", "ug", "uk", "ur", "uz", "ve", "vi", "vo",
+    "wa", "wo", "xh", "yi", "yo", "za", "zh", "zu",
+];
+
+/// The user's locale rules compiled against the scan root, and the locales
+/// whose files may contain Chinese.
+pub(crate) struct LocaleMap {
+    /// Checked last to first, so later rules win.
+    rules: Vec<(Override, String)>,
+    chinese: Vec<String>,
+}
+
+impl LocaleMap {
+    pub(crate) fn new(
+        root: &Path,
+        rules: &[(String, String)],
+        chinese: &[String],
+    ) -> Result<Self, String> {
+        let rules = rules
+            .iter()
+            .map(|(pattern, locale)| {
+                let mut builder = OverrideBuilder::new(root);
+                builder.add(pattern).map_err(|e| e.to_string())?;
+                let matcher = builder.build().map_err(|e| e.to_string())?;
+                Ok((matcher, locale.clone()))
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self {
+            rules,
+            chinese: chinese.to_vec(),
+        })
+    }
+
+    /// The locale a rule assigns to the file, if any.
+    pub(crate) fn rule(&self, file_path: &Path) -> Option<&str> {
+        self.rules
+            .iter()
+            .rev()
+            .find(|(matcher, _)| matcher.matched(file_path, false).is_whitelist())
+            .map(|(_, locale)| locale.as_str())
+    }
+
+    /// Whether Chinese is expected in `locale`'s files. `zh` covers `zh-CN`,
+    /// `zh_TW`, `zh-Hans` and so on.
+    pub(crate) fn allows_chinese(&self, locale: &str) -> bool {
+        self.chinese.iter().any(|chinese| {
+            locale.eq_ignore_ascii_case(chinese)
+                || locale
+                    .get(..chinese.len())
+                    .is_some_and(|prefix| prefix.eq_ignore_ascii_case(chinese))
+                    && matches!(locale.as_bytes().get(chinese.len()), Some(b'-' | b'_'))
+        })
+    }
+}
+
+/// Splits a `PATTERN=LOCALE` rule.
+pub(crate) fn parse_rule(rule: &str) -> Result<(String, String), String> {
+    rule.rsplit_once('=')
+        .map(|(pattern, locale)| (pattern.trim(), locale.trim()))
+        .filter(|(pattern, locale)| !pattern.is_empty() && !locale.is_empty())
+        .map(|(pattern, locale)| (pattern.to_string(), locale.to_string()))
+        .ok_or_else(|| format!("Expected PATTERN=LOCALE, got '{}'", rule))
+}
+
+/// Guesses the locale of a resource file from its path relative to the root.
+pub(crate) fn detect(file_path: &Path) -> Option<String> {
+    let stem = file_path.file_stem()?.to_str()?;
+    let extension = file_path.extension().and_then(|e| e.to_str());
+    if let Some(locale) = stem_locale(stem, extension) {
+        return Some(locale.to_string());
+    }
+    file_path
+        .parent()?
+        .ancestors()
+        .filter_map(|dir| dir.file_name()?.to_str())
+        .find_map(directory_locale)
+}
+
+/// `en`, `zh-Hans.lproj`, Android `values-zh-rCN` (as `zh-CN`).
+fn directory_locale(name: &str) -> Option<String> {
+    if let Some(qualifiers) = name.strip_prefix("values-") {
+        return android_locale(qualifiers);
+    }
+    let name = name.strip_suffix(".lproj").unwrap_or(name);
+    is_locale(name).then(|| name.to_string())
+}
+
+/// The locale in Android resource qualifiers, which may follow a mobile
+/// country and network code: `en`, `zh-rTW-night`, `mcc460-zh-rCN`,
+/// `b+zh+Hans+CN`.
+fn android_locale(qualifiers: &str) -> Option<String> {
+    let mut parts = qualifiers
+        .split('-')
+        .skip_while(|part| part.starts_with("mcc") || part.starts_with("mnc"));
+    let language = parts.next()?;
+    if let Some(tag) = language.strip_prefix("b+") {
+        let tag = tag.replace('+', "-");
+        return is_locale(&tag).then_some(tag);
+    }
+    if !LANGUAGES.contains(&language) {
+        return None;
+    }
+    let region = parts
+        .next()
+        .and_then(|part| part.strip_prefix('r'))
+        .filter(|region| region.len() == 2 && region.bytes().all(|b| b.is_ascii_uppercase()));
+    Some(match region {
+        Some(region) => format!("{}-{}", language, region),
+        None => language.to_string(),
+    })
+}
+
+/// `en`, `app.en`, and for Java bundles and ARB files `messages_zh_CN`.
+fn stem_locale<'a>(stem: &'a str, extension: Option<&str>) -> Option<&'a str> {
+    if is_locale(stem) {
+        return Some(stem);
+    }
+    if let Some((_, last)) = stem.rsplit_once('.') {
+        if is_locale(last) {
+            return Some(last);
+        }
+    }
+    // Java bundles and Flutter ARB files put the locale after a `_`:
+    // `messages_zh_CN`, `app_en`. Elsewhere that reads as a word, as in
+    // `user_id.json` or `page_it.json`.
+    if !matches!(extension, Some("properties" | "arb")) {
+        return None;
+    }
+    stem.match_indices('_')
+        .map(|(i, _)| &stem[i + 1..])
+        .find(|rest| is_locale(rest))
+}
+
+/// A language code, optionally followed by a script and region: `en`,
+/// `zh-CN`, `zh_Hant_TW`, `es-419`.
+fn is_locale(name: &str) -> bool {
+    let mut parts = name.split(['-', '_']);
+    let language = parts.next().unwrap_or_default();
+    if !LANGUAGES.contains(&language) {
+        return false;
+    }
+    parts.all(|part| {
+        let script = part.len() == 4 && part.bytes().all(|b| b.is_ascii_alphabetic());
+        let region = part.len() == 2 && part.bytes().all(|b| b.is_ascii_alphabetic())
+            || part.len() == 3 && part.bytes().all(|b| b.is_ascii_digit());
+        script || region
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::detect;
+    use crate::scanner::testing::TempTree;
+    use crate::scanner::{ScanOptions, Scanner, SkipReason};
+    use std::path::Path;
+
+    fn locale(path: &str) -> Option<String> {
+        detect(Path::new(path))
+    }
+
+    #[test]
+    fn locales_from_file_names() {
+        assert_eq!(locale("src/i18n/en.json").as_deref(), Some("en"));
+        assert_eq!(locale("config/app.zh-CN.yml").as_deref(), Some("zh-CN"));
+        assert_eq!(
+            locale("res/messages_zh_TW.properties").as_deref(),
+            Some("zh_TW")
+        );
+        assert_eq!(locale("lib/l10n/app_en.arb").as_deref(), Some("en"));
+    }
+
+    #[test]
+    fn locales_from_directories() {
+        assert_eq!(locale("locales/fr/common.json").as_deref(), Some("fr"));
+        assert_eq!(
+            locale("locale/de/LC_MESSAGES/app.po").as_deref(),
+            Some("de")
+        );
+        assert_eq!(
+            locale("res/values-zh-rCN/strings.xml").as_deref(),
+            Some("zh-CN")
+        );
+        assert_eq!(
+            locale("ios/zh-Hans.lproj/Localizable.strings").as_deref(),
+            Some("zh-Hans")
+        );
+    }
+
+    #[test]
+    fn words_after_underscores_are_not_locales() {
+        assert_eq!(locale("src/user_id.json"), None);
+        assert_eq!(locale("field_no.yaml"), None);
+        assert_eq!(locale("page_it.json"), None);
+        assert_eq!(locale("src/js/ui.json"), None);
+    }
+
+    #[test]
+    fn findings_carry_the_locale_and_chinese_locales_are_skipped() {
+        let tree = TempTree::new(&[
+            ("locales/en/app.json", "{\"save\": \"保存\"}"),
+            ("locales/zh-CN/app.json", "{\"save\": \"保存\"}"),
+            ("src/user_id.json", "{\"name\": \"名字\"}"),
+        ]);
+        let options = ScanOptions::new(tree.path()).locale("src/*.json", "fr");
+        let report = Scanner::scan(&options).unwrap();
+        let found: Vec<_> = report
+            .results
+            .iter()
+            .map(|result| (result.file_path.as_str(), result.locale.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("locales/en/app.json", Some("en")),
+                ("src/user_id.json", Some("fr"))
+            ]
+        );
+        let planned = Scanner::dry_run(&options).unwrap();
+        let chinese = planned
+            .files
+            .iter()
+            .find(|file| file.file_path == "locales/zh-CN/app.json")
+            .unwrap();
+        assert_eq!(chinese.skipped, Some(SkipReason::ChineseLocale));
+    }
+}
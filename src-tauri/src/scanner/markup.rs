@@ -0,0 +1,427 @@
+//! A forgiving tokenizer for HTML-like markup: Vue, Svelte and Astro templates,
+//! and anything else made of tags, attributes and text.
+//!
+//! It doesn't build a tree or validate nesting, it only splits the source into
+//! tokens with byte offsets into the whole file. Text containing interpolations
+//! such as `{{ a < b }}` is kept in one piece when the delimiters are set with
+//! [`Tokenizer::with_interpolation`].
+
+pub(crate) struct Attribute<'s> {
+    pub(crate) name: &'s str,
+    pub(crate) value: Option<AttributeValue<'s>>,
+}
+
+pub(crate) struct AttributeValue<'s> {
+    /// The value without its quotes or braces.
+    pub(crate) text: &'s str,
+    pub(crate) start: usize,
+    /// Written as `name={...}` rather than quoted.
+    pub(crate) braced: bool,
+}
+
+pub(crate) enum Token<'s> {
+    Text {
+        text: &'s str,
+        start: usize,
+    },
+    StartTag {
+        name: &'s str,
+        attributes: Vec<Attribute<'s>>,
+        self_closing: bool,
+    },
+    EndTag {
+        name: &'s str,
+    },
+    Comment,
+}
+
+pub(crate) struct Tokenizer<'s> {
+    source: &'s str,
+    pos: usize,
+    end: usize,
+    interpolation: Option<(&'static str, &'static str)>,
+}
+
+impl<'s> Tokenizer<'s> {
+    /// Tokenizes `source[start..end]`; offsets in tokens are into all of `source`.
+    pub(crate) fn new(source: &'s str, start: usize, end: usize) -> Self {
+        Self {
+            source,
+            pos: start,
+            end,
+            interpolation: None,
+        }
+    }
+
+    /// Keeps text between `open` and `close` together, so `<` or `>` inside an
+    /// expression don't start a tag. A single `{` is matched with nesting.
+    pub(crate) fn with_interpolation(mut self, open: &'static str, close: &'static str) -> Self {
+        self.interpolation = Some((open, close));
+        self
+    }
+
+    /// Consumes the content of a raw text element such as `<script>` up to and
+    /// including its end tag, and returns the content and where it starts.
+    pub(crate) fn raw_text(&mut self, name: &str) -> (&'s str, usize) {
+        let start = self.pos;
+        let bytes = self.source.as_bytes();
+        let mut i = start;
+        while i < self.end {
+            if bytes[i] == b'<'
+                && bytes.get(i + 1) == Some(&b'/')
+                && self.source[i + 2..self.end]
+                    .get(..name.len())
+                    .is_some_and(|tag| tag.eq_ignore_ascii_case(name))
+            {
+                let content = &self.source[start..i];
+                self.pos = find_from(self.source, ">", i, self.end).map_or(self.end, |p| p + 1);
+                return (content, start);
+            }
+            i += 1;
+        }
+        self.pos = self.end;
+        (&self.source[start..self.end], start)
+    }
+
+    fn text(&mut self) -> Token<'s> {
+        let start = self.pos;
+        let bytes = self.source.as_bytes();
+        let mut i = start;
+        while i < self.end {
+            if let Some((open, close)) = self.interpolation {
+                if bytes[i..self.end].starts_with(open.as_bytes()) {
+                    i = if open == "{" {
+                        skip_balanced(self.source, i, self.end)
+                    } else {
+                        find_from(self.source, close, i + open.len(), self.end)
+                            .map_or(self.end, |p| p + close.len())
+                    };
+                    continue;
+                }
+            }
+            if bytes[i] == b'<' && starts_tag(bytes, i + 1) {
+                break;
+            }
+            i += 1;
+        }
+        self.pos = i;
+        Token::Text {
+            text: &self.source[start..i],
+            start,
+        }
+    }
+
+    fn tag(&mut self) -> Option<Token<'s>> {
+        let bytes = self.source.as_bytes();
+        let rest = &self.source[self.pos..self.end];
+
+        if rest.starts_with("<!--") {
+            let end = find_from(self.source, "-->", self.pos + 4, self.end).unwrap_or(self.end);
+            self.pos = (end + 3).min(self.end);
+            return Some(Token::Comment);
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            // Doctype or processing instruction.
+            self.pos = find_from(self.source, ">", self.pos, self.end).map_or(self.end, |p| p + 1);
+            return None;
+        }
+        if rest.starts_with("</") {
+            let name_start = self.pos + 2;
+            let name_end = self.scan_name(name_start);
+            self.pos = find_from(self.source, ">", name_end, self.end).map_or(self.end, |p| p + 1);
+            return Some(Token::EndTag {
+                name: &self.source[name_start..name_end],
+            });
+        }
+
+        let name_start = self.pos + 1;
+        let name_end = self.scan_name(name_start);
+        let name = &self.source[name_start..name_end];
+        let mut attributes = Vec::new();
+        let mut i = name_end;
+        let mut self_closing = false;
+
+        while i < self.end {
+            match bytes[i] {
+                b'>' => {
+                    i += 1;
+                    break;
+                }
+                b'/' if bytes.get(i + 1) == Some(&b'>') => {
+                    self_closing = true;
+                    i += 2;
+                    break;
+                }
+                b if b.is_ascii_whitespace() || b == b'/' => i += 1,
+                b'{' => {
+                    // `{...props}` or `{shorthand}`
+                    let end = skip_balanced(self.source, i, self.end);
+                    attributes.push(Attribute {
+                        name: &self.source[i..end],
+                        value: None,
+                    });
+                    i = end;
+                }
+                _ => {
+                    let attr_start = i;
+                    while i < self.end && !ends_attribute_name(bytes, i) {
+                        i += 1;
+                    }
+                    let attr_name = &self.source[attr_start..i];
+                    let mut j = skip_whitespace(bytes, i, self.end);
+                    let mut value = None;
+                    if bytes.get(j) == Some(&b'=') {
+                        j = skip_whitespace(bytes, j + 1, self.end);
+                        let (parsed, next) = self.attribute_value(j);
+                        value = parsed;
+                        i = next;
+                    }
+                    attributes.push(Attribute {
+                        name: attr_name,
+                        value,
+                    });
+                }
+            }
+        }
+
+        self.pos = i.min(self.end);
+        Some(Token::StartTag {
+            name,
+            attributes,
+            self_closing,
+        })
+    }
+
+    fn attribute_value(&self, i: usize) -> (Option<AttributeValue<'s>>, usize) {
+        let bytes = self.source.as_bytes();
+        match bytes.get(i) {
+            Some(&quote @ (b'"' | b'\'')) => {
+                let start = i + 1;
+                let end = self.source[start..self.end]
+                    .find(quote as char)
+                    .map_or(self.end, |p| start + p);
+                let value = AttributeValue {
+                    text: &self.source[start..end],
+                    start,
+                    braced: false,
+                };
+                (Some(value), (end + 1).min(self.end))
+            }
+            Some(b'{') => {
+                let end = skip_balanced(self.source, i, self.end);
+                let inner_end = if end > i + 1 && bytes[end - 1] == b'}' {
+                    end - 1
+                } else {
+                    end
+                };
+                let value = AttributeValue {
+                    text: &self.source[i + 1..inner_end],
+                    start: i + 1,
+                    braced: true,
+                };
+                (Some(value), end)
+            }
+            Some(_) => {
+                let mut end = i;
+                while end < self.end && !bytes[end].is_ascii_whitespace() && bytes[end] != b'>' {
+                    end += 1;
+                }
+                let value = AttributeValue {
+                    text: &self.source[i..end],
+                    start: i,
+                    braced: false,
+                };
+                (Some(value), end)
+            }
+            None => (None, i),
+        }
+    }
+
+    fn scan_name(&self, start: usize) -> usize {
+        let bytes = self.source.as_bytes();
+        let mut i = start;
+        while i < self.end && !bytes[i].is_ascii_whitespace() && !matches!(bytes[i], b'/' | b'>') {
+            i += 1;
+        }
+        i
+    }
+}
+
+impl<'s> Iterator for Tokenizer<'s> {
+    type Item = Token<'s>;
+
+    fn next(&mut self) -> Option<Token<'s>> {
+        let bytes = self.source.as_bytes();
+        while self.pos < self.end {
+            if bytes[self.pos] == b'<' && starts_tag(bytes, self.pos + 1) {
+                if let Some(token) = self.tag() {
+                    return Some(token);
+                }
+            } else {
+                return Some(self.text());
+            }
+        }
+        None
+    }
+}
+
+/// Looks up an attribute's value by name.
+pub(crate) fn attribute_value<'a, 's>(
+    attributes: &'a [Attribute<'s>],
+    name: &str,
+) -> Option<&'a AttributeValue<'s>> {
+    attributes
+        .iter()
+        .find(|attribute| attribute.name == name)
+        .and_then(|attribute| attribute.value.as_ref())
+}
+
+/// A piece of text split around interpolations.
+pub(crate) enum Segment<'s> {
+    Static { text: &'s str, start: usize },
+    Expression { code: &'s str, start: usize },
+}
+
+/// Splits `text`, which starts at `start`, into static parts and the code
+/// inside `open`/`close` delimiters.
+pub(crate) fn split_interpolations<'s>(
+    text: &'s str,
+    start: usize,
+    open: &str,
+    close: &str,
+) -> Vec<Segment<'s>> {
+    let mut segments = Vec::new();
+    let mut pos = 0;
+    while let Some(found) = text[pos..].find(open) {
+        let open_at = pos + found;
+        let code_start = open_at + open.len();
+        let code_end = if open == "{" {
+            let end = skip_balanced(text, open_at, text.len());
+            if text.as_bytes().get(end - 1) == Some(&b'}') {
+                end - 1
+            } else {
+                end
+            }
+        } else {
+            text[code_start..]
+                .find(close)
+                .map_or(text.len(), |p| code_start + p)
+        };
+        if open_at > pos {
+            segments.push(Segment::Static {
+                text: &text[pos..open_at],
+                start: start + pos,
+            });
+        }
+        segments.push(Segment::Expression {
+            code: &text[code_start..code_end],
+            start: start + code_start,
+        });
+        pos = (code_end + close.len()).min(text.len());
+    }
+    if pos < text.len() {
+        segments.push(Segment::Static {
+            text: &text[pos..],
+            start: start + pos,
+        });
+    }
+    segments
+}
+
+/// Whether the byte after a `<` starts a tag, comment or declaration.
+fn starts_tag(bytes: &[u8], i: usize) -> bool {
+    match bytes.get(i) {
+        Some(b) if b.is_ascii_alphabetic() => true,
+        Some(b'!' | b'?') => true,
+        Some(b'/') => bytes.get(i + 1).is_some_and(|b| b.is_ascii_alphabetic()),
+        _ => false,
+    }
+}
+
+fn ends_attribute_name(bytes: &[u8], i: usize) -> bool {
+    bytes[i].is_ascii_whitespace()
+        || matches!(bytes[i], b'=' | b'>')
+        || (bytes[i] == b'/' && bytes.get(i + 1) == Some(&b'>'))
+}
+
+fn skip_whitespace(bytes: &[u8], mut i: usize, end: usize) -> usize {
+    while i < end && bytes[i].is_ascii_whitespace() {
+        i += 1;
+    }
+    i
+}
+
+fn find_from(source: &str, needle: &str, from: usize, end: usize) -> Option<usize> {
+    source[from..end].find(needle).map(|p| from + p)
+}
+
+/// Given a `{` at `open`, returns the offset just past its matching `}`,
+/// skipping over braces inside string and template literals.
+pub(crate) fn skip_balanced(source: &str, open: usize, end: usize) -> usize {
+    let bytes = source.as_bytes();
+    let mut depth = 0;
+    let mut i = open;
+    while i < end {
+        match bytes[i] {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            quote @ (b'"' | b'\'' | b'`') => {
+                i += 1;
+                while i < end && bytes[i] != quote {
+                    if bytes[i] == b'\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    end
+}
+
+/// Decodes the predefined XML entities and numeric character references.
+/// Unknown entities are kept as written.
+pub(crate) fn decode_entities(text: &str) -> String {
+    let mut value = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        value.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').and_then(|semi| {
+            let c = match &rest[1..semi] {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                name => {
+                    let code = match name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => name.strip_prefix('#')?.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((c, semi + 1))
+        });
+        match decoded {
+            Some((c, len)) => {
+                value.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                value.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    value.push_str(rest);
+    value
+}
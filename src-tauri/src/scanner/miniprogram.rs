@@ -0,0 +1,76 @@
+//! WeChat and Alipay mini-programs.
+//!
+//! `.wxml`/`.axml` templates are markup with `{{ }}` interpolation in text and
+//! attribute values, and inline `<wxs>`/`<sjs>` modules are scanned as script.
+//! Page and app `.json` configs are scanned for string values such as
+//! `navigationBarTitleText`.
+
+use super::component::{scan_markup, Dialect};
+use super::file::{FileContext, FileScan};
+use super::json;
+use oxc::allocator::Allocator;
+use oxc::span::SourceType;
+use std::fs;
+use std::path::Path;
+
+pub(crate) fn scan_template(ctx: &FileContext, allocator: &Allocator) -> FileScan {
+    let dialect = Dialect {
+        delimiters: ("{{", "}}"),
+        script_tags: &["wxs", "sjs"],
+        // WXS is CommonJS.
+        default_script: SourceType::default(),
+        expression: SourceType::default(),
+        logic_blocks: false,
+        interpolate_quoted: true,
+    };
+    let mut out = FileScan::default();
+    scan_markup(ctx, allocator, 0, ctx.source_text.len(), &dialect, &mut out);
+    out
+}
+
+pub(crate) fn scan_config(ctx: &FileContext) -> FileScan {
+    json::scan_json(ctx)
+}
+
+/// Whether a `.json` file is a mini-program config: a page or component
+/// config next to a template with the same name, or an `app.json` or
+/// `ext.json` next to templates or a `pages/` directory holding them. Another
+/// `app.json`, such as one in `locales/zh-CN/`, is left to be read as JSON.
+pub(crate) fn is_config(file_path: &Path) -> bool {
+    if TEMPLATE_EXTENSIONS
+        .iter()
+        .any(|extension| file_path.with_extension(extension).is_file())
+    {
+        return true;
+    }
+    if !matches!(
+        file_path.file_name().and_then(|name| name.to_str()),
+        Some("app.json" | "ext.json")
+    ) {
+        return false;
+    }
+    file_path
+        .parent()
+        .is_some_and(|dir| has_template(dir, 0) || has_template(&dir.join("pages"), 2))
+}
+
+const TEMPLATE_EXTENSIONS: [&str; 2] = ["wxml", "axml"];
+
+/// Whether `dir`, or a directory up to `depth` levels below it, holds a
+/// template.
+fn has_template(dir: &Path, depth: usize) -> bool {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => depth > 0 && has_template(&path, depth - 1),
+            Ok(_) => path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| TEMPLATE_EXTENSIONS.contains(&extension)),
+            Err(_) => false,
+        }
+    })
+}
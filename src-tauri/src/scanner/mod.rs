@@ -0,0 +1,1000 @@
+//! The scan engine, independent of Tauri.
+//!
+//! ```no_run
+//! use app_lib::scanner::{ScanOptions, Scanner};
+//!
+//! let options = ScanOptions::new("./src").exclude("**/*.test.ts");
+//! let report = Scanner::scan(&options).unwrap();
+//! for result in &report.results {
+//!     println!("{}:{}:{}: {}", result.file_path, result.line, result.column, result.text);
+//! }
+//! ```
+
+mod android;
+mod apple;
+mod arb;
+mod astro;
+mod component;
+mod config;
+mod escape;
+mod fallback;
+mod file;
+mod file_type;
+mod html;
+mod js;
+mod json;
+mod key_path;
+mod locale;
+mod markup;
+mod miniprogram;
+mod po;
+mod position;
+mod progress;
+mod properties;
+mod pug;
+mod report;
+mod script;
+mod segment;
+mod svelte;
+mod syntax;
+#[cfg(test)]
+mod testing;
+mod vue;
+mod yaml;
+
+pub use config::ScanConfig;
+pub use file_type::FileType;
+pub use position::ColumnUnit;
+pub use progress::{ScanObserver, ScanProgress};
+pub use report::{
+    Confidence, DiagnosticKind, DryRunReport, FindingContext, FindingKind, MatchRange, PlannedFile,
+    ScanDiagnostic, ScanReport, ScanResult, SkipReason,
+};
+pub use script::Script;
+
+use config::{ConfigTree, Profile, WalkSettings};
+use file::{FileContext, FileScan};
+use ignore::{DirEntry, WalkBuilder, WalkState};
+use oxc::allocator::Allocator;
+use oxc::span::SourceType;
+use position::LineIndex;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use syntax::Grammar;
+
+/// What to scan and how. Built with chained setters:
+/// `ScanOptions::new(root).exclude("dist").exclude("**/*.d.ts")`.
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    root: PathBuf,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    threads: usize,
+    file_types: Vec<(String, FileType)>,
+    skip_declarations: bool,
+    locales: Vec<(String, String)>,
+    chinese_locales: Vec<String>,
+    comments: bool,
+    identifiers: bool,
+    column_unit: Option<ColumnUnit>,
+    split_segments: bool,
+    scripts: Option<Vec<Script>>,
+    pattern: Option<String>,
+    ignore_text: Vec<String>,
+    config: bool,
+    gitignore: Option<bool>,
+    skip_hidden: bool,
+    follow_links: bool,
+    max_depth: Option<usize>,
+    max_file_size: Option<u64>,
+}
+
+impl ScanOptions {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            threads: 0,
+            file_types: Vec::new(),
+            skip_declarations: false,
+            locales: Vec::new(),
+            chinese_locales: vec!["zh".to_string()],
+            comments: false,
+            identifiers: false,
+            column_unit: None,
+            split_segments: false,
+            scripts: None,
+            pattern: None,
+            ignore_text: Vec::new(),
+            config: true,
+            gitignore: None,
+            skip_hidden: false,
+            follow_links: false,
+            max_depth: None,
+            max_file_size: None,
+        }
+    }
+
+    /// Adds a glob, relative to the root, that files must match to be
+    /// scanned. With no include globs every file is a candidate. Excludes
+    /// and `.gitignore` take precedence: an included file they leave out
+    /// isn't scanned.
+    pub fn include(mut self, pattern: impl Into<String>) -> Self {
+        self.include.push(pattern.into());
+        self
+    }
+
+    /// Adds several include globs at once.
+    pub fn includes<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.include.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Adds include globs from a comma separated list, as typed in the UI.
+    pub fn include_list(self, list: &str) -> Self {
+        self.includes(list.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()))
+    }
+
+    /// Adds a glob, relative to the root, whose matches are not scanned.
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.exclude.push(pattern.into());
+        self
+    }
+
+    /// Adds several exclude globs at once.
+    pub fn excludes<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.exclude.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Adds exclude globs from a comma separated list, as typed in the UI.
+    pub fn exclude_list(self, list: &str) -> Self {
+        self.excludes(list.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()))
+    }
+
+    /// Number of worker threads for walking and parsing. `0`, the default,
+    /// picks one based on the available CPUs.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// Scans files matching `pattern` as `file_type`, whatever their extension.
+    /// The pattern is a glob relative to the root such as `legacy/**/*.js`, or
+    /// a bare extension such as `es6`. Later rules take precedence over earlier
+    /// ones and over the built-in mapping.
+    pub fn file_type(mut self, pattern: impl Into<String>, file_type: FileType) -> Self {
+        self.file_types.push((pattern.into(), file_type));
+        self
+    }
+
+    /// Adds a file type rule written as `PATTERN=TYPE`, e.g. `es6=js`.
+    pub fn file_type_rule(mut self, rule: &str) -> Result<Self, String> {
+        self.file_types.push(file_type::parse_rule(rule)?);
+        Ok(self)
+    }
+
+    /// Adds file type rules from a comma separated list, as typed in the UI.
+    pub fn file_type_list(self, list: &str) -> Result<Self, String> {
+        list.split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .try_fold(self, |options, rule| options.file_type_rule(rule))
+    }
+
+    /// Skips `.d.ts`, `.d.mts` and `.d.cts` declaration files.
+    pub fn skip_declarations(mut self, skip: bool) -> Self {
+        self.skip_declarations = skip;
+        self
+    }
+
+    /// Marks files matching the glob, relative to the root, as resources for
+    /// `locale`. Without a rule the locale is guessed from the path, e.g.
+    /// `en.json` or `locales/en/common.json`. Later rules take precedence.
+    pub fn locale(mut self, pattern: impl Into<String>, locale: impl Into<String>) -> Self {
+        self.locales.push((pattern.into(), locale.into()));
+        self
+    }
+
+    /// Adds a locale rule written as `PATTERN=LOCALE`, e.g. `i18n/*.json=en`.
+    pub fn locale_rule(self, rule: &str) -> Result<Self, String> {
+        let (pattern, locale) = locale::parse_rule(rule)?;
+        Ok(self.locale(pattern, locale))
+    }
+
+    /// Adds locale rules from a comma separated list, as typed in the UI.
+    pub fn locale_list(self, list: &str) -> Result<Self, String> {
+        list.split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .try_fold(self, |options, rule| options.locale_rule(rule))
+    }
+
+    /// Adds a locale whose files are expected to contain Chinese, so they
+    /// aren't scanned, e.g. `ja`. Every `zh` locale is one by default.
+    pub fn chinese_locale(mut self, locale: impl Into<String>) -> Self {
+        self.chinese_locales.push(locale.into());
+        self
+    }
+
+    /// Also reports Chinese in comments, as [`FindingKind::Comment`]: line,
+    /// block and JSX comments in scripts and components, and comments in Java,
+    /// Go, Python (including docstrings) and Kotlin.
+    pub fn comments(mut self, comments: bool) -> Self {
+        self.comments = comments;
+        self
+    }
+
+    /// Also reports Chinese in names in scripts and components: declared
+    /// identifiers, unquoted property keys and import specifiers, each with its
+    /// own [`FindingKind`]. The relative path of every scanned file is checked
+    /// too, as [`FindingKind::FilePath`].
+    pub fn identifiers(mut self, identifiers: bool) -> Self {
+        self.identifiers = identifiers;
+        self
+    }
+
+    /// What columns count: bytes (the default), chars or UTF-16 code units.
+    /// Byte offsets in results are always bytes.
+    pub fn column_unit(mut self, unit: ColumnUnit) -> Self {
+        self.column_unit = Some(unit);
+        self
+    }
+
+    /// The scripts to look for, replacing the default of [`Script::Han`] and
+    /// any set in a config file. Each finding lists the ones it contains.
+    pub fn scripts(mut self, scripts: impl IntoIterator<Item = Script>) -> Self {
+        self.scripts = Some(scripts.into_iter().collect());
+        self
+    }
+
+    /// Sets the scripts from a comma separated list of names such as
+    /// `han,hiragana,katakana`, as typed in the UI. An empty list keeps the
+    /// current ones.
+    pub fn script_list(self, list: &str) -> Result<Self, String> {
+        let scripts = list
+            .split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<Script>, String>>()?;
+        Ok(if scripts.is_empty() {
+            self
+        } else {
+            self.scripts(scripts)
+        })
+    }
+
+    /// Also looks for matches of a regex, reported as [`Script::Custom`].
+    /// Call `scripts([])` first to look for nothing else.
+    pub fn pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.pattern = Some(pattern.into());
+        self
+    }
+
+    /// Drops findings whose text matches the regex `pattern`, e.g. `^https?://`.
+    pub fn ignore_text(mut self, pattern: impl Into<String>) -> Self {
+        self.ignore_text.push(pattern.into());
+        self
+    }
+
+    /// Reads a [`ScanConfig`] from `chinese-scan.toml` or `.chinesescanrc`
+    /// in the root and in any directory below it, on by default. Rules set
+    /// here are added after a config's, and settings turned on here apply
+    /// everywhere.
+    pub fn config(mut self, config: bool) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Reports each run of Chinese in a string, text node or comment as its
+    /// own finding, rather than one finding per node listing the runs in
+    /// [`ScanResult::matches`].
+    pub fn split_segments(mut self, split: bool) -> Self {
+        self.split_segments = split;
+        self
+    }
+
+    /// Whether `.gitignore`, `.ignore` and git's global and repository
+    /// excludes leave files out, on by default. Turn it off to scan build
+    /// output that is ignored. `.chinesescanignore` files, written like
+    /// `.gitignore`, apply either way.
+    pub fn gitignore(mut self, gitignore: bool) -> Self {
+        self.gitignore = Some(gitignore);
+        self
+    }
+
+    /// Skips hidden files and directories, whose names start with `.`.
+    pub fn skip_hidden(mut self, skip: bool) -> Self {
+        self.skip_hidden = skip;
+        self
+    }
+
+    /// Follows symbolic links. Off by default, so links, such as the ones
+    /// pnpm puts in `node_modules`, are left out.
+    pub fn follow_links(mut self, follow: bool) -> Self {
+        self.follow_links = follow;
+        self
+    }
+
+    /// How many directories deep to walk: `1` scans only the files in the
+    /// root.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Skips files larger than `bytes`, such as vendored bundles.
+    pub fn max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = Some(bytes);
+        self
+    }
+
+    /// Sets the maximum file size from text such as `1M` or `500K`, as typed
+    /// in the UI.
+    pub fn max_file_size_text(self, size: &str) -> Result<Self, String> {
+        Ok(self.max_file_size(parse_size(size)?))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+pub struct Scanner;
+
+impl Scanner {
+    /// Walks the root and returns every Chinese string found in the files a
+    /// [`FileType`] is picked for: by the options' rules, by extension, or for
+    /// extensionless node and Python scripts by their `#!` line. `.gitignore`
+    /// is respected unless [`ScanOptions::gitignore`] turns it off.
+    ///
+    /// Files are walked and parsed on `options.threads` workers; results are sorted
+    /// by path, line and column so the output doesn't depend on scheduling.
+    pub fn scan(options: &ScanOptions) -> Result<ScanReport, String> {
+        Self::scan_with(options, &())
+    }
+
+    /// Same as [`Scanner::scan`], reporting progress and each file's findings
+    /// to `observer` as the scan runs.
+    pub fn scan_with(
+        options: &ScanOptions,
+        observer: &dyn ScanObserver,
+    ) -> Result<ScanReport, String> {
+        let results = Mutex::new(Vec::new());
+        let diagnostics = Mutex::new(Vec::new());
+        let files_discovered = AtomicUsize::new(0);
+        let files_scanned = AtomicUsize::new(0);
+        let findings = AtomicUsize::new(0);
+        let cancelled = AtomicBool::new(false);
+        let path = options.root.as_path();
+
+        if !path.is_dir() {
+            return Err(format!("Path is not a directory: {}", path.display()));
+        }
+
+        let configs = ConfigTree::new(path, options)?;
+
+        walker(options, &configs.walk).build_parallel().run(|| {
+            // Each worker reuses one arena until it grows too large.
+            let mut allocator = Allocator::default();
+            let results = &results;
+            let diagnostics = &diagnostics;
+            let files_discovered = &files_discovered;
+            let files_scanned = &files_scanned;
+            let findings = &findings;
+            let cancelled = &cancelled;
+            let configs = &configs;
+
+            Box::new(move |result| {
+                if observer.is_cancelled() {
+                    cancelled.store(true, Ordering::Relaxed);
+                    return WalkState::Quit;
+                }
+
+                let entry = match result {
+                    Ok(entry) => entry,
+                    Err(err) => {
+                        diagnostics
+                            .lock()
+                            .unwrap()
+                            .push(walk_diagnostic(path, &err));
+                        return WalkState::Continue;
+                    }
+                };
+
+                let file_path = entry.path();
+                let Some(dir) = file_path.parent().filter(|_| entry.depth() > 0) else {
+                    return WalkState::Continue;
+                };
+                let (profile, config_problem) = configs.profile(dir);
+                if let Some(message) = config_problem {
+                    diagnostics
+                        .lock()
+                        .unwrap()
+                        .push(config_diagnostic(path, dir, message));
+                }
+                let relative_path = file_path.strip_prefix(path).unwrap_or(file_path);
+                let (file_type, locale) =
+                    match visit(&profile, &configs.walk, &entry, relative_path) {
+                        Visit::Scan { file_type, locale } => (file_type, locale),
+                        Visit::Enter => return WalkState::Continue,
+                        Visit::Skip(_) if entry.file_type().is_some_and(|t| t.is_dir()) => {
+                            return WalkState::Skip
+                        }
+                        Visit::Skip(_) => return WalkState::Continue,
+                    };
+                let locales = &profile.locales;
+                let language = file_type.language();
+
+                // Counted as soon as the walker yields it, before it is read.
+                let discovered = files_discovered.fetch_add(1, Ordering::Relaxed) + 1;
+                let current_file = relative_path.to_string_lossy().to_string();
+                observer.on_progress(&ScanProgress {
+                    files_discovered: discovered,
+                    files_scanned: files_scanned.load(Ordering::Relaxed),
+                    findings: findings.load(Ordering::Relaxed),
+                    current_file: current_file.clone(),
+                });
+
+                // A panic would stall the parallel walk, so report it like a parse error.
+                let scanned = panic::catch_unwind(AssertUnwindSafe(|| {
+                    scan_file(
+                        &allocator,
+                        file_path,
+                        relative_path,
+                        language,
+                        &profile,
+                        configs.column_unit,
+                    )
+                }));
+                let FileScan {
+                    results: mut file_results,
+                    diagnostics: file_diagnostics,
+                } = scanned.unwrap_or_else(|_| {
+                    allocator = Allocator::default();
+                    FileScan {
+                        results: Vec::new(),
+                        diagnostics: vec![ScanDiagnostic {
+                            file_path: current_file.clone(),
+                            kind: DiagnosticKind::Parse,
+                            message: "Internal error while scanning this file".to_string(),
+                            line: None,
+                            column: None,
+                        }],
+                    }
+                });
+                // String catalogs hold several locales and tag their own findings.
+                for result in &mut file_results {
+                    if result.locale.is_none() {
+                        result.locale = locale.clone();
+                    }
+                    result.config = profile.config.clone();
+                }
+                file_results.retain(|result| {
+                    !result
+                        .locale
+                        .as_deref()
+                        .is_some_and(|locale| locales.allows_chinese(locale))
+                });
+                findings.fetch_add(file_results.len(), Ordering::Relaxed);
+                if !file_results.is_empty() {
+                    observer.on_file_results(&current_file, &file_results);
+                }
+                observer.on_progress(&ScanProgress {
+                    files_discovered: files_discovered.load(Ordering::Relaxed),
+                    files_scanned: files_scanned.fetch_add(1, Ordering::Relaxed) + 1,
+                    findings: findings.load(Ordering::Relaxed),
+                    current_file,
+                });
+                if !file_results.is_empty() {
+                    results.lock().unwrap().extend(file_results);
+                }
+                if !file_diagnostics.is_empty() {
+                    diagnostics.lock().unwrap().extend(file_diagnostics);
+                }
+
+                if allocator.allocated_bytes() > ALLOCATOR_RESET_BYTES {
+                    allocator = Allocator::default();
+                }
+                WalkState::Continue
+            })
+        });
+
+        let mut final_results = results.into_inner().unwrap();
+        final_results.sort_by(|a, b| {
+            (&a.file_path, a.line, a.column).cmp(&(&b.file_path, b.line, b.column))
+        });
+        let mut final_diagnostics = diagnostics.into_inner().unwrap();
+        final_diagnostics.sort_by(|a, b| {
+            (&a.file_path, a.line, a.column).cmp(&(&b.file_path, b.line, b.column))
+        });
+        let mut script_counts = BTreeMap::new();
+        for result in &final_results {
+            for &script in &result.scripts {
+                *script_counts.entry(script).or_insert(0) += 1;
+            }
+        }
+        Ok(ScanReport {
+            results: final_results,
+            script_counts,
+            configs: configs.configs(),
+            files_scanned: files_scanned.into_inner(),
+            diagnostics: final_diagnostics,
+            cancelled: cancelled.into_inner(),
+        })
+    }
+}
+
+impl Scanner {
+    /// Walks the root like [`Scanner::scan`] without reading any file, and
+    /// lists each file it would scan and each file or directory it would
+    /// leave out, with the reason. A directory left out as a whole is listed
+    /// once rather than file by file.
+    pub fn dry_run(options: &ScanOptions) -> Result<DryRunReport, String> {
+        let path = options.root.as_path();
+        if !path.is_dir() {
+            return Err(format!("Path is not a directory: {}", path.display()));
+        }
+        let configs = ConfigTree::new(path, options)?;
+        let files = Mutex::new(Vec::new());
+        let diagnostics = Mutex::new(Vec::new());
+        // Everything the walker yielded, and the directories it went into.
+        let seen = Mutex::new(HashSet::new());
+        let entered = Mutex::new(vec![path.to_path_buf()]);
+
+        walker(options, &configs.walk).build_parallel().run(|| {
+            let (files, diagnostics, seen, entered) = (&files, &diagnostics, &seen, &entered);
+            let configs = &configs;
+            Box::new(move |result| {
+                let entry = match result {
+                    Ok(entry) => entry,
+                    Err(err) => {
+                        diagnostics
+                            .lock()
+                            .unwrap()
+                            .push(walk_diagnostic(path, &err));
+                        return WalkState::Continue;
+                    }
+                };
+                let file_path = entry.path();
+                let Some(dir) = file_path.parent().filter(|_| entry.depth() > 0) else {
+                    return WalkState::Continue;
+                };
+                seen.lock().unwrap().insert(file_path.to_path_buf());
+                let (profile, config_problem) = configs.profile(dir);
+                if let Some(message) = config_problem {
+                    diagnostics
+                        .lock()
+                        .unwrap()
+                        .push(config_diagnostic(path, dir, message));
+                }
+                let relative_path = file_path.strip_prefix(path).unwrap_or(file_path);
+                let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+                let mut planned = PlannedFile {
+                    file_path: relative_path.to_string_lossy().to_string(),
+                    is_dir,
+                    file_type: None,
+                    locale: None,
+                    skipped: None,
+                    config: profile.config.clone(),
+                };
+                let state = match visit(&profile, &configs.walk, &entry, relative_path) {
+                    Visit::Enter => {
+                        entered.lock().unwrap().push(file_path.to_path_buf());
+                        return WalkState::Continue;
+                    }
+                    Visit::Scan { file_type, locale } => {
+                        planned.file_type = Some(file_type);
+                        planned.locale = locale;
+                        WalkState::Continue
+                    }
+                    Visit::Skip(reason) => {
+                        planned.skipped = Some(reason);
+                        if is_dir {
+                            WalkState::Skip
+                        } else {
+                            WalkState::Continue
+                        }
+                    }
+                };
+                files.lock().unwrap().push(planned);
+                state
+            })
+        });
+
+        let mut files = files.into_inner().unwrap();
+        let mut diagnostics = diagnostics.into_inner().unwrap();
+        let seen = seen.into_inner().unwrap();
+        let entered = entered.into_inner().unwrap();
+        // The walker leaves out hidden and ignored entries without yielding them.
+        let skip_hidden = configs.walk.skip_hidden;
+        for dir in &entered {
+            let Ok(children) = fs::read_dir(dir) else {
+                continue;
+            };
+            for child in children.flatten() {
+                let child_path = child.path();
+                if seen.contains(&child_path) {
+                    continue;
+                }
+                let relative_path = child_path.strip_prefix(path).unwrap_or(&child_path);
+                let hidden = child.file_name().to_string_lossy().starts_with('.');
+                files.push(PlannedFile {
+                    file_path: relative_path.to_string_lossy().to_string(),
+                    is_dir: child.file_type().is_ok_and(|t| t.is_dir()),
+                    file_type: None,
+                    locale: None,
+                    skipped: Some(if skip_hidden && hidden {
+                        SkipReason::Hidden
+                    } else {
+                        SkipReason::Ignored
+                    }),
+                    config: None,
+                });
+            }
+        }
+
+        files.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        diagnostics.sort_by(|a, b| {
+            (&a.file_path, a.line, a.column).cmp(&(&b.file_path, b.line, b.column))
+        });
+        Ok(DryRunReport {
+            files,
+            configs: configs.configs(),
+            diagnostics,
+        })
+    }
+}
+
+/// Ignore files written like `.gitignore` that only this scanner reads.
+const IGNORE_FILE_NAME: &str = ".chinesescanignore";
+
+/// A walker over the root that applies the ignore files and hidden files of
+/// `walk`; everything else is decided by [`visit`].
+fn walker(options: &ScanOptions, walk: &WalkSettings) -> WalkBuilder {
+    let mut walk_builder = WalkBuilder::new(&options.root);
+    walk_builder
+        .hidden(walk.skip_hidden)
+        .ignore(walk.gitignore)
+        .git_ignore(walk.gitignore)
+        .git_global(walk.gitignore)
+        .git_exclude(walk.gitignore)
+        .parents(walk.gitignore)
+        .add_custom_ignore_filename(IGNORE_FILE_NAME)
+        .follow_links(walk.follow_links)
+        .threads(options.threads);
+    walk_builder
+}
+
+/// Parses a file size: bytes, or with a `K`, `M` or `G` suffix for KiB, MiB
+/// or GiB, optionally followed by `B`.
+pub(crate) fn parse_size(size: &str) -> Result<u64, String> {
+    let size = size.trim();
+    let invalid = || format!("Invalid size '{}', expected e.g. 500K or 1M", size);
+    let upper = size.to_ascii_uppercase();
+    let number = upper.strip_suffix('B').unwrap_or(&upper);
+    let (number, unit) = match number.char_indices().last() {
+        Some((i, 'K')) => (&number[..i], 1 << 10),
+        Some((i, 'M')) => (&number[..i], 1 << 20),
+        Some((i, 'G')) => (&number[..i], 1 << 30),
+        _ => (number, 1),
+    };
+    number
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .and_then(|number| number.checked_mul(unit))
+        .ok_or_else(invalid)
+}
+
+/// What the walk does with an entry.
+enum Visit {
+    /// Scan the file as `file_type`, as resources for `locale` if it has one.
+    Scan {
+        file_type: FileType,
+        locale: Option<String>,
+    },
+    /// Walk into the directory.
+    Enter,
+    /// Leave the file, or the whole directory, out.
+    Skip(SkipReason),
+}
+
+/// Decides what to do with an entry the walker found, in order of
+/// precedence: version control directories, excludes, then includes, then
+/// the file's type and locale, then its size.
+fn visit(profile: &Profile, walk: &WalkSettings, entry: &DirEntry, relative_path: &Path) -> Visit {
+    let file_path = entry.path();
+    let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+    if is_dir && is_version_control(entry) {
+        return Visit::Skip(SkipReason::VersionControl);
+    }
+    if profile.excludes(file_path, is_dir) {
+        return Visit::Skip(SkipReason::Excluded);
+    }
+    if is_dir {
+        return match walk.max_depth {
+            Some(depth) if entry.depth() >= depth => Visit::Skip(SkipReason::TooDeep),
+            _ => Visit::Enter,
+        };
+    }
+    // With links followed the walker reports their targets' types instead.
+    if entry.path_is_symlink() && !walk.follow_links {
+        return Visit::Skip(SkipReason::Symlink);
+    }
+    if !entry.file_type().is_some_and(|t| t.is_file()) {
+        return Visit::Skip(SkipReason::NotAFile);
+    }
+    if profile.leaves_out(file_path) {
+        return Visit::Skip(SkipReason::NotIncluded);
+    }
+    if profile.file_types.skips_declaration(file_path) {
+        return Visit::Skip(SkipReason::Declaration);
+    }
+    let locales = &profile.locales;
+    let rule_locale = locales.rule(file_path).map(String::from);
+    let detected_locale = match rule_locale {
+        Some(_) => None,
+        None => locale::detect(relative_path),
+    };
+    let has_locale = rule_locale.is_some() || detected_locale.is_some();
+    let Some(file_type) = profile.file_types.file_type(file_path, has_locale) else {
+        return Visit::Skip(SkipReason::UnknownType);
+    };
+    // A guessed locale only counts for resource files; `es/` next to
+    // source code is more likely ES modules than Spanish.
+    let locale = rule_locale.or(detected_locale.filter(|_| file_type.is_resource()));
+    if locale
+        .as_deref()
+        .is_some_and(|locale| locales.allows_chinese(locale))
+    {
+        return Visit::Skip(SkipReason::ChineseLocale);
+    }
+    if let Some(max) = walk.max_file_size {
+        if entry.metadata().is_ok_and(|metadata| metadata.len() > max) {
+            return Visit::Skip(SkipReason::TooLarge);
+        }
+    }
+    Visit::Scan { file_type, locale }
+}
+
+/// Directories a version control system keeps its own files in. They are
+/// hidden, but walked when hidden files are, so they are left out by name.
+const VERSION_CONTROL_DIRS: [&str; 3] = [".git", ".hg", ".svn"];
+
+fn is_version_control(entry: &DirEntry) -> bool {
+    entry
+        .file_name()
+        .to_str()
+        .is_some_and(|name| VERSION_CONTROL_DIRS.contains(&name))
+}
+
+fn config_diagnostic(root: &Path, dir: &Path, message: String) -> ScanDiagnostic {
+    ScanDiagnostic {
+        file_path: dir
+            .strip_prefix(root)
+            .unwrap_or(dir)
+            .to_string_lossy()
+            .to_string(),
+        kind: DiagnosticKind::Config,
+        message,
+        line: None,
+        column: None,
+    }
+}
+
+/// Once a worker's arena holds this much, it is dropped and a fresh one started.
+const ALLOCATOR_RESET_BYTES: usize = 64 * 1024 * 1024;
+
+/// How a file is scanned.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Language {
+    /// JavaScript or TypeScript, parsed directly by oxc.
+    Script(SourceType),
+    /// `.js`, which may be a module or a classic script.
+    JavaScript,
+    /// Vue single-file component.
+    Vue,
+    Svelte,
+    Astro,
+    /// WeChat `.wxml` or Alipay `.axml` template.
+    MiniProgramTemplate,
+    MiniProgramConfig,
+    Html,
+    Ejs,
+    Handlebars,
+    Pug,
+    Json,
+    Yaml,
+    Properties,
+    Po,
+    AndroidStrings,
+    AppleStrings,
+    StringsDict,
+    StringCatalog,
+    Arb,
+    /// Java, Go, Python or Kotlin, parsed with tree-sitter.
+    Syntax(Grammar),
+}
+
+/// Reads one file and scans it with the extractor for its language.
+fn scan_file(
+    allocator: &Allocator,
+    file_path: &Path,
+    relative_path: &Path,
+    language: Language,
+    profile: &Profile,
+    column_unit: ColumnUnit,
+) -> FileScan {
+    let display_path = relative_path.to_string_lossy().to_string();
+    let source_text = match read_source(file_path) {
+        Ok(text) => text,
+        Err(err) => {
+            return FileScan {
+                results: Vec::new(),
+                diagnostics: vec![ScanDiagnostic {
+                    file_path: display_path,
+                    kind: DiagnosticKind::Read,
+                    message: err.to_string(),
+                    line: None,
+                    column: None,
+                }],
+            }
+        }
+    };
+
+    let ctx = FileContext {
+        file_path: &display_path,
+        source_text: &source_text,
+        chinese_regex: &profile.scripts.regex,
+        scripts: &profile.scripts,
+        lines: LineIndex::new(&source_text, column_unit),
+        comments: profile.comments,
+        identifiers: profile.identifiers,
+    };
+    let mut out = match language {
+        Language::Script(source_type) => {
+            js::scan_script(&ctx, allocator, &source_text, 0, source_type)
+        }
+        Language::JavaScript => js::scan_javascript(&ctx, allocator, &source_text),
+        Language::Vue => vue::scan_vue(&ctx, allocator),
+        Language::Svelte => svelte::scan_svelte(&ctx, allocator),
+        Language::Astro => astro::scan_astro(&ctx, allocator),
+        Language::MiniProgramTemplate => miniprogram::scan_template(&ctx, allocator),
+        Language::MiniProgramConfig => miniprogram::scan_config(&ctx),
+        Language::Html => html::scan_html(&ctx, allocator),
+        Language::Ejs => html::scan_ejs(&ctx, allocator),
+        Language::Handlebars => html::scan_handlebars(&ctx, allocator),
+        Language::Pug => pug::scan_pug(&ctx, allocator),
+        Language::Json => json::scan_json(&ctx),
+        Language::Yaml => yaml::scan_yaml(&ctx),
+        Language::Properties => properties::scan_properties(&ctx),
+        Language::Po => po::scan_po(&ctx),
+        Language::AndroidStrings => android::scan_strings(&ctx),
+        Language::AppleStrings => apple::scan_strings(&ctx),
+        Language::StringsDict => apple::scan_stringsdict(&ctx),
+        Language::StringCatalog => apple::scan_catalog(&ctx),
+        Language::Arb => arb::scan_arb(&ctx),
+        Language::Syntax(grammar) => syntax::scan_source(&ctx, grammar),
+    };
+    if ctx.identifiers && ctx.chinese_regex.is_match(&display_path) {
+        out.results.push(ctx.result(
+            Vec::new(),
+            &display_path,
+            Confidence::High,
+            FindingKind::FilePath,
+        ));
+    }
+    out.results.retain(|result| !profile.ignores(&result.text));
+    if profile.split_segments {
+        out.results = out
+            .results
+            .into_iter()
+            .flat_map(ScanResult::split)
+            .collect();
+        for result in &mut out.results {
+            result.scripts = ctx.scripts.scripts_in(&result.text);
+        }
+    }
+    out
+}
+
+/// Reads a file as UTF-8, or as UTF-16 when it starts with a UTF-16 byte
+/// order mark, as `.strings` files written by older Xcode versions do.
+fn read_source(file_path: &Path) -> io::Result<String> {
+    let bytes = fs::read(file_path)?;
+    let utf16 = |to_unit: fn([u8; 2]) -> u16| {
+        let units: Vec<u16> = bytes[2..]
+            .chunks_exact(2)
+            .map(|pair| to_unit([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16(&units).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    };
+    match bytes.get(..2) {
+        Some([0xff, 0xfe]) => utf16(u16::from_le_bytes),
+        Some([0xfe, 0xff]) => utf16(u16::from_be_bytes),
+        _ => String::from_utf8(bytes).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stream did not contain valid UTF-8",
+            )
+        }),
+    }
+}
+
+/// Turns a walker error into a diagnostic, using the path it carries if any.
+fn walk_diagnostic(root: &Path, err: &ignore::Error) -> ScanDiagnostic {
+    let mut file_path = String::new();
+    let mut inner = err;
+    loop {
+        match inner {
+            ignore::Error::WithPath { path, err } => {
+                file_path = path
+                    .strip_prefix(root)
+                    .unwrap_or(path)
+                    .to_string_lossy()
+                    .to_string();
+                inner = err;
+            }
+            ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => {
+                inner = err;
+            }
+            _ => break,
+        }
+    }
+    ScanDiagnostic {
+        file_path,
+        kind: DiagnosticKind::Walk,
+        message: err.to_string(),
+        line: None,
+        column: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::TempTree;
+    use super::{FindingKind, ScanOptions, Scanner};
+
+    #[test]
+    fn names_and_paths_only_when_identifiers_are_on() {
+        let source = "import { 工具 as 助手 } from \"./a\";\nconst 名称 = { 键: 1 };\nfunction 处理(参数) {}\n";
+        let tree = TempTree::new(&[("页面/index.js", source)]);
+        let options = ScanOptions::new(tree.path());
+        assert!(Scanner::scan(&options).unwrap().results.is_empty());
+
+        let report = Scanner::scan(&options.identifiers(true)).unwrap();
+        let found: Vec<_> = report
+            .results
+            .iter()
+            .map(|result| (result.text.as_str(), result.kind))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("页面/index.js", FindingKind::FilePath),
+                ("工具", FindingKind::ImportSpecifier),
+                ("助手", FindingKind::ImportSpecifier),
+                ("名称", FindingKind::Identifier),
+                ("键", FindingKind::PropertyKey),
+                ("处理", FindingKind::Identifier),
+                ("参数", FindingKind::Identifier),
+            ]
+        );
+    }
+}
@@ -0,0 +1,174 @@
+//! gettext `.po` catalogs.
+//!
+//! Only translations (`msgstr`) are checked; `msgid` is the source text. Each
+//! is reported under its `msgid`, prefixed with `msgctxt|` when the entry has a
+//! context and suffixed with `[n]` for plural forms. The header entry and
+//! obsolete `#~` entries are skipped.
+
+use super::file::{FileContext, FileScan};
+
+/// A quoted string on one line: the raw content and where it starts.
+type Part<'s> = (&'s str, usize);
+
+#[derive(Default)]
+struct Entry<'s> {
+    context: Option<Vec<Part<'s>>>,
+    id: Vec<Part<'s>>,
+    /// `msgstr` or `msgstr[n]`, with `n`.
+    translations: Vec<(Option<usize>, Vec<Part<'s>>)>,
+}
+
+enum Field {
+    Context,
+    Id,
+    Plural,
+    Translation,
+}
+
+pub(crate) fn scan_po(ctx: &FileContext) -> FileScan {
+    let source = ctx.source_text;
+    let mut out = FileScan::default();
+    let mut entry = Entry::default();
+    let mut field = None;
+    let mut line_start = if source.starts_with('\u{feff}') { 3 } else { 0 };
+
+    while line_start < source.len() {
+        let line_end = source[line_start..]
+            .find('\n')
+            .map_or(source.len(), |p| line_start + p);
+        let line = source[line_start..line_end].trim_end_matches('\r');
+        let content = line.trim_start();
+        let content_start = line_start + line.len() - content.len();
+        line_start = line_end + 1;
+
+        if content.is_empty() || content.starts_with('#') {
+            continue;
+        }
+        let Some(quote) = content.find('"') else {
+            continue;
+        };
+        let keyword = content[..quote].trim_end();
+        let part = quoted_part(content, quote, content_start);
+
+        let starts_entry = matches!(keyword, "msgctxt" | "msgid");
+        if starts_entry && !entry.translations.is_empty() {
+            check_entry(ctx, &std::mem::take(&mut entry), &mut out);
+        }
+        match keyword {
+            "" => match field {
+                Some(Field::Context) => entry.context.get_or_insert_with(Vec::new).push(part),
+                Some(Field::Id) => entry.id.push(part),
+                Some(Field::Translation) => {
+                    if let Some((_, parts)) = entry.translations.last_mut() {
+                        parts.push(part);
+                    }
+                }
+                Some(Field::Plural) | None => {}
+            },
+            "msgctxt" => {
+                entry.context = Some(vec![part]);
+                field = Some(Field::Context);
+            }
+            "msgid" => {
+                entry.id = vec![part];
+                field = Some(Field::Id);
+            }
+            "msgid_plural" => field = Some(Field::Plural),
+            _ => {
+                let Some(rest) = keyword.strip_prefix("msgstr") else {
+                    field = None;
+                    continue;
+                };
+                let index = rest
+                    .strip_prefix('[')
+                    .and_then(|rest| rest.strip_suffix(']'))
+                    .and_then(|index| index.parse().ok());
+                entry.translations.push((index, vec![part]));
+                field = Some(Field::Translation);
+            }
+        }
+    }
+    check_entry(ctx, &entry, &mut out);
+    out
+}
+
+/// The content of the string whose opening quote is at `quote`.
+fn quoted_part(content: &str, quote: usize, content_start: usize) -> Part<'_> {
+    let bytes = content.as_bytes();
+    let start = quote + 1;
+    let mut i = start;
+    while i < bytes.len() && bytes[i] != b'"' {
+        if bytes[i] == b'\\' {
+            i += 1;
+        }
+        i += 1;
+    }
+    (&content[start..i.min(bytes.len())], content_start + start)
+}
+
+fn check_entry(ctx: &FileContext, entry: &Entry, out: &mut FileScan) {
+    let id = decode(&entry.id);
+    if id.is_empty() {
+        // The header.
+        return;
+    }
+    let key = match &entry.context {
+        Some(context) => format!("{}|{}", decode(context), id),
+        None => id,
+    };
+    for (index, parts) in &entry.translations {
+        // A string split over lines is mapped line by line, so the range
+        // runs from the first line with Chinese to the last.
+        let segments = parts
+            .iter()
+            .flat_map(|(raw, start)| ctx.raw_segments(&unescape(raw), raw, *start, true))
+            .collect();
+        let key = match index {
+            Some(index) => format!("{}[{}]", key, index),
+            None => key.clone(),
+        };
+        ctx.check_segments(segments, &decode(parts), &key, out);
+    }
+}
+
+fn decode(parts: &[Part]) -> String {
+    parts.iter().map(|(raw, _)| unescape(raw)).collect()
+}
+
+/// Decodes C escapes.
+fn unescape(raw: &str) -> String {
+    let mut value = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('t') => value.push('\t'),
+            Some('r') => value.push('\r'),
+            Some(other) => value.push(other),
+            None => {}
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::scan_po;
+    use crate::scanner::file::{covered, keyed, scan_test_source};
+
+    #[test]
+    fn translations_under_msgid() {
+        let source = "msgid \"\"\nmsgstr \"\"\n\nmsgctxt \"menu\"\nmsgid \"Open\"\nmsgstr \"打开\"\n\nmsgid \"file\"\nmsgid_plural \"files\"\nmsgstr[0] \"文件\"\nmsgstr[1] \"多个\"\n\"文件\"\n";
+        let results = scan_test_source(source, scan_po);
+        assert_eq!(results.len(), 3);
+        assert_eq!(covered(source, keyed(&results, "menu|Open")), "打开");
+        assert_eq!(covered(source, keyed(&results, "file[0]")), "文件");
+        let continued = keyed(&results, "file[1]");
+        assert_eq!(continued.text, "多个文件");
+        assert_eq!(covered(source, continued), "多个\"\n\"文件");
+    }
+}
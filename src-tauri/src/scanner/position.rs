@@ -0,0 +1,161 @@
+//! Byte offsets to line and column numbers.
+
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// What a column counts. Lines and columns are 1-based either way.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ColumnUnit {
+    /// UTF-8 bytes, the default.
+    #[default]
+    Byte,
+    /// Unicode scalar values, as Vim and most terminals count.
+    Char,
+    /// UTF-16 code units, as VS Code, LSP and JavaScript count.
+    Utf16,
+}
+
+impl ColumnUnit {
+    pub const ALL: [ColumnUnit; 3] = [ColumnUnit::Byte, ColumnUnit::Char, ColumnUnit::Utf16];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ColumnUnit::Byte => "byte",
+            ColumnUnit::Char => "char",
+            ColumnUnit::Utf16 => "utf16",
+        }
+    }
+
+    fn width(self, text: &str) -> usize {
+        match self {
+            ColumnUnit::Byte => text.len(),
+            ColumnUnit::Char => text.chars().count(),
+            ColumnUnit::Utf16 => text.encode_utf16().count(),
+        }
+    }
+}
+
+impl fmt::Display for ColumnUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ColumnUnit {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, String> {
+        let name = name.trim();
+        ColumnUnit::ALL
+            .into_iter()
+            .find(|unit| unit.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                format!(
+                    "Unknown column unit '{}', expected one of: byte, char, utf16",
+                    name
+                )
+            })
+    }
+}
+
+/// Where each line of a file starts, built once per file so each lookup is a
+/// binary search rather than a rescan from the top.
+///
+/// `\n`, `\r\n` and a lone `\r` all end a line. A byte order mark at the
+/// start of the file is not counted as a column.
+pub(crate) struct LineIndex {
+    line_starts: Vec<usize>,
+    /// Length of the byte order mark, if any.
+    bom: usize,
+    unit: ColumnUnit,
+}
+
+impl LineIndex {
+    pub(crate) fn new(source_text: &str, unit: ColumnUnit) -> Self {
+        let bom = if source_text.starts_with('\u{feff}') {
+            '\u{feff}'.len_utf8()
+        } else {
+            0
+        };
+        let mut line_starts = vec![0];
+        let bytes = source_text.as_bytes();
+        for (i, &b) in bytes.iter().enumerate() {
+            let ends_line = b == b'\n' || (b == b'\r' && bytes.get(i + 1) != Some(&b'\n'));
+            if ends_line {
+                line_starts.push(i + 1);
+            }
+        }
+        Self {
+            line_starts,
+            bom,
+            unit,
+        }
+    }
+
+    /// Line and column of the byte `offset` into `source_text`, the text the
+    /// index was built from. Offsets inside a `\r\n` or a multi-byte
+    /// character count up to the start of it.
+    pub(crate) fn line_col(&self, source_text: &str, offset: usize) -> (usize, usize) {
+        let mut offset = offset.min(source_text.len());
+        while !source_text.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let line_start = match line {
+            0 => self.bom.min(offset),
+            _ => self.line_starts[line],
+        };
+        let prefix = &source_text[line_start..offset];
+        // A `\r` just before a `\n` belongs to the line ending.
+        let prefix = prefix.strip_suffix('\r').unwrap_or(prefix);
+        (line + 1, self.unit.width(prefix) + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColumnUnit, LineIndex};
+
+    fn line_col(text: &str, offset: usize, unit: ColumnUnit) -> (usize, usize) {
+        LineIndex::new(text, unit).line_col(text, offset)
+    }
+
+    #[test]
+    fn bom_is_not_a_column() {
+        let text = "\u{feff}中文\n文";
+        assert_eq!(line_col(text, 3, ColumnUnit::Byte), (1, 1));
+        assert_eq!(line_col(text, 6, ColumnUnit::Byte), (1, 4));
+        assert_eq!(line_col(text, 6, ColumnUnit::Char), (1, 2));
+        assert_eq!(line_col(text, 10, ColumnUnit::Char), (2, 1));
+    }
+
+    #[test]
+    fn line_endings() {
+        let text = "a\r\nb\rc\nd";
+        assert_eq!(line_col(text, 1, ColumnUnit::Byte), (1, 2));
+        // Inside the `\r\n`.
+        assert_eq!(line_col(text, 2, ColumnUnit::Byte), (1, 2));
+        assert_eq!(line_col(text, 3, ColumnUnit::Byte), (2, 1));
+        assert_eq!(line_col(text, 5, ColumnUnit::Byte), (3, 1));
+        assert_eq!(line_col(text, 7, ColumnUnit::Byte), (4, 1));
+    }
+
+    #[test]
+    fn offset_inside_a_character() {
+        let text = "中文";
+        assert_eq!(line_col(text, 1, ColumnUnit::Byte), (1, 1));
+        assert_eq!(line_col(text, 4, ColumnUnit::Byte), (1, 4));
+        assert_eq!(line_col(text, 4, ColumnUnit::Utf16), (1, 2));
+    }
+
+    #[test]
+    fn column_units() {
+        // `b` follows a one-byte, a three-byte and a four-byte character.
+        let text = "x\na中😀b";
+        assert_eq!(line_col(text, 10, ColumnUnit::Byte), (2, 9));
+        assert_eq!(line_col(text, 10, ColumnUnit::Char), (2, 4));
+        assert_eq!(line_col(text, 10, ColumnUnit::Utf16), (2, 5));
+    }
+}
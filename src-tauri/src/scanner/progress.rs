@@ -0,0 +1,39 @@
+use super::ScanResult;
+use serde::Serialize;
+
+/// A snapshot of how far a scan has got.
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanProgress {
+    /// Source files the walker has found so far. Files are scanned as soon as
+    /// they are found, so this is a running count rather than a total: it
+    /// stays a few files, one per worker, ahead of `files_scanned`.
+    pub files_discovered: usize,
+    /// Source files that have been processed so far.
+    pub files_scanned: usize,
+    /// Findings reported so far.
+    pub findings: usize,
+    /// Path, relative to the root, of the file the update is about.
+    pub current_file: String,
+}
+
+/// Receives updates while a scan runs, and can ask it to stop.
+///
+/// Methods are called from the walker's worker threads, possibly at the same
+/// time, so implementations must be cheap and thread safe.
+pub trait ScanObserver: Sync {
+    /// Called when a file is discovered and again once it has been scanned.
+    fn on_progress(&self, _progress: &ScanProgress) {}
+
+    /// Called once per scanned file that produced findings.
+    fn on_file_results(&self, _file_path: &str, _results: &[ScanResult]) {}
+
+    /// Checked between files; once it returns `true` the walk stops and the
+    /// scan returns what it found so far, marked as cancelled.
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+/// Ignores every update.
+impl ScanObserver for () {}
@@ -0,0 +1,128 @@
+//! Java `.properties` resource bundles.
+//!
+//! Values are reported under their key. Lines continued with a trailing `\`
+//! are joined and `\uXXXX` escapes decoded, since bundles are often kept in
+//! ASCII with `native2ascii`.
+
+use super::file::{FileContext, FileScan};
+
+pub(crate) fn scan_properties(ctx: &FileContext) -> FileScan {
+    let source = ctx.source_text;
+    let mut out = FileScan::default();
+    let mut line_start = if source.starts_with('\u{feff}') { 3 } else { 0 };
+
+    while line_start < source.len() {
+        let mut line_end = find_line_end(source, line_start);
+        let content_start = skip_blank(source, line_start, line_end);
+        let first = source[content_start..line_end].chars().next();
+        if matches!(first, None | Some('#' | '!')) {
+            line_start = line_end + 1;
+            continue;
+        }
+        // A logical line goes on while a line ends with an odd number of `\`.
+        while line_end < source.len() && ends_with_continuation(&source[line_start..line_end]) {
+            line_end = find_line_end(source, line_end + 1);
+        }
+        let logical = &source[content_start..line_end];
+
+        let key_end = key_end(logical);
+        let key = unescape(&logical[..key_end]);
+        let mut value_start = content_start + key_end;
+        value_start = skip_blank(source, value_start, line_end);
+        if matches!(source.as_bytes().get(value_start), Some(b'=' | b':')) && value_start < line_end
+        {
+            value_start = skip_blank(source, value_start + 1, line_end);
+        }
+        let raw = source[value_start..line_end].trim_end_matches('\r');
+        ctx.check_value(&unescape(raw), raw, value_start, &key, &mut out);
+
+        line_start = line_end + 1;
+    }
+    out
+}
+
+fn find_line_end(source: &str, from: usize) -> usize {
+    source[from..].find('\n').map_or(source.len(), |p| from + p)
+}
+
+fn skip_blank(source: &str, mut i: usize, end: usize) -> usize {
+    let bytes = source.as_bytes();
+    while i < end && matches!(bytes[i], b' ' | b'\t' | b'\x0c') {
+        i += 1;
+    }
+    i
+}
+
+fn ends_with_continuation(line: &str) -> bool {
+    let line = line.trim_end_matches('\r');
+    let backslashes = line.len() - line.trim_end_matches('\\').len();
+    backslashes % 2 == 1
+}
+
+/// The end of the key: the first unescaped `=`, `:` or whitespace.
+fn key_end(logical: &str) -> usize {
+    let bytes = logical.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'=' | b':' | b' ' | b'\t' | b'\x0c' | b'\r' | b'\n' => return i,
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len().min(i)
+}
+
+/// Decodes escapes and joins continued lines.
+fn unescape(raw: &str) -> String {
+    let mut value = String::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                let decoded = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+                value.push(decoded.unwrap_or('\u{fffd}'));
+            }
+            Some('\r' | '\n') => {
+                // A continued line; its leading whitespace isn't part of the value.
+                while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                    chars.next();
+                }
+            }
+            Some('t') => value.push('\t'),
+            Some('n') => value.push('\n'),
+            Some('r') => value.push('\r'),
+            Some('f') => value.push('\x0c'),
+            Some(other) => value.push(other),
+            None => {}
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::scan_properties;
+    use crate::scanner::file::{covered, keyed, scan_test_source};
+
+    #[test]
+    fn values_under_keys() {
+        let source =
+            "# 注释\ngreeting = 你好\nwrapped=第一行\\\n    第二行\nescaped: \\u4e2d\\u6587\n";
+        let results = scan_test_source(source, scan_properties);
+        assert_eq!(results.len(), 3);
+        assert_eq!(covered(source, keyed(&results, "greeting")), "你好");
+        let wrapped = keyed(&results, "wrapped");
+        assert_eq!(wrapped.text, "第一行第二行");
+        assert_eq!(covered(source, wrapped), "第一行\\\n    第二行");
+        let escaped = keyed(&results, "escaped");
+        assert_eq!(escaped.text, "中文");
+        assert_eq!(covered(source, escaped), "\\u4e2d\\u6587");
+    }
+}
@@ -0,0 +1,418 @@
+//! Pug (formerly Jade) templates.
+//!
+//! Pug is indentation-based, so it is scanned line by line instead of with the
+//! markup tokenizer. Text after a tag, piped `| text`, `tag.` text blocks and
+//! user-visible attributes are checked; `#{}` interpolations, `= expr` output,
+//! `- code` lines, conditions and mixin arguments go through the oxc visitor,
+//! as do `script.` blocks. Comments and `style.` blocks are skipped.
+
+use super::fallback;
+use super::file::{FileContext, FileScan};
+use super::html::VISIBLE_ATTRIBUTES;
+use super::js;
+use super::markup::{split_interpolations, Segment};
+use oxc::allocator::Allocator;
+use oxc::span::SourceType;
+
+/// What the lines indented under a line hold.
+enum Block {
+    /// `tag.` text or a `:filter`.
+    Text,
+    /// `script.` or a bare `-` line; the code starts at the offset.
+    Script(usize),
+    /// Comments and `style.`.
+    Skip,
+}
+
+/// Keywords whose line has no text of its own.
+const SKIPPED_KEYWORDS: &[&str] = &[
+    "append", "block", "default", "doctype", "else", "extends", "include", "mixin", "prepend",
+    "yield",
+];
+
+/// Keywords followed by an expression.
+const EXPRESSION_KEYWORDS: &[&str] = &["case", "else if", "if", "unless", "when", "while"];
+
+pub(crate) fn scan_pug(ctx: &FileContext, allocator: &Allocator) -> FileScan {
+    let source = ctx.source_text;
+    let mut scanner = PugScanner {
+        ctx,
+        allocator,
+        out: FileScan::default(),
+    };
+    // The indentation and kind of the open block, and where its last line ends.
+    let mut block: Option<(usize, Block)> = None;
+    let mut block_end = 0;
+    let mut line_start = if source.starts_with('\u{feff}') { 3 } else { 0 };
+
+    while line_start < source.len() {
+        let line_end = source[line_start..]
+            .find('\n')
+            .map_or(source.len(), |p| line_start + p);
+        let line = source[line_start..line_end].trim_end_matches('\r');
+        let content = line.trim_start();
+        let indent = line.len() - content.len();
+        let content_start = line_start + indent;
+        let next_line = line_end + 1;
+
+        if let Some((block_indent, kind)) = &block {
+            if content.is_empty() || indent > *block_indent {
+                if let Block::Text = kind {
+                    scanner.text(content, content_start);
+                }
+                block_end = line_end;
+                line_start = next_line;
+                continue;
+            }
+            if let Block::Script(start) = *kind {
+                scanner.script(start, block_end.max(start));
+            }
+            block = None;
+        }
+
+        if !content.is_empty() {
+            block = scanner.line(content, content_start).map(|kind| {
+                let kind = match kind {
+                    Block::Script(_) => Block::Script(next_line.min(source.len())),
+                    kind => kind,
+                };
+                (indent, kind)
+            });
+            block_end = line_end;
+        }
+        line_start = next_line;
+    }
+    if let Some((_, Block::Script(start))) = block {
+        scanner.script(start, block_end.max(start));
+    }
+
+    scanner.out
+}
+
+struct PugScanner<'c, 'a> {
+    ctx: &'c FileContext<'c>,
+    allocator: &'a Allocator,
+    out: FileScan,
+}
+
+impl PugScanner<'_, '_> {
+    /// Scans one line; returns the kind of block its indented lines form, if
+    /// they aren't ordinary Pug.
+    fn line(&mut self, content: &str, start: usize) -> Option<Block> {
+        if content.starts_with("//") {
+            return Some(Block::Skip);
+        }
+        if let Some(text) = content.strip_prefix('|') {
+            self.text(text, start + 1);
+            return None;
+        }
+        if let Some(code) = content.strip_prefix('-') {
+            if code.trim().is_empty() {
+                return Some(Block::Script(0));
+            }
+            self.code(code, start + 1);
+            return None;
+        }
+        if let Some(rest) = content.strip_prefix(':') {
+            // `:markdown` and other filters; `:filter text` on one line too.
+            if let Some(space) = rest.find(' ') {
+                self.text(&rest[space..], start + 1 + space);
+            }
+            return Some(Block::Text);
+        }
+        if content.starts_with('<') {
+            // Inline HTML.
+            self.text(content, start);
+            return None;
+        }
+        if let Some((offset, code)) = keyword_expression(content) {
+            self.expression(code, start + offset);
+            return None;
+        }
+        if let Some(call) = content.strip_prefix('+') {
+            return self.tag(call, start + 1, true);
+        }
+        self.tag(content, start, false)
+    }
+
+    /// Scans a tag line: `p#id.class(title="标题") 文本`, `a: img`, `p= expr`.
+    /// For a mixin call, the first parentheses hold its arguments.
+    fn tag(&mut self, content: &str, start: usize, mixin: bool) -> Option<Block> {
+        let bytes = content.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            let next_is_name = bytes.get(i + 1).is_some_and(|&b| is_name_byte(b));
+            if is_name_byte(bytes[i]) || (matches!(bytes[i], b'.' | b'#') && next_is_name) {
+                i += 1;
+            } else {
+                break;
+            }
+        }
+        let name_end = content[..i].find(['.', '#']).unwrap_or(i);
+        let name = &content[..name_end];
+
+        let mut arguments = mixin;
+        while bytes.get(i) == Some(&b'(') {
+            let close = matching_paren(content, i);
+            if arguments {
+                self.expression(&content[i + 1..close], start + i + 1);
+                arguments = false;
+            } else {
+                self.attributes(&content[i + 1..close], start + i + 1);
+            }
+            i = (close + 1).min(content.len());
+            // `&attributes(...)`
+            if content[i..].starts_with("&attributes") {
+                i += "&attributes".len();
+            }
+        }
+
+        let rest = &content[i..];
+        if rest == "." {
+            return Some(if name.eq_ignore_ascii_case("script") {
+                Block::Script(0)
+            } else if name.eq_ignore_ascii_case("style") {
+                Block::Skip
+            } else {
+                Block::Text
+            });
+        }
+        if let Some(nested) = rest.strip_prefix(": ") {
+            // Block expansion.
+            let lead = nested.len() - nested.trim_start().len();
+            return self.tag(nested.trim_start(), start + i + 2 + lead, false);
+        }
+        if let Some(code) = rest.strip_prefix("!=").or_else(|| rest.strip_prefix('=')) {
+            self.expression(code, start + content.len() - code.len());
+            return None;
+        }
+        if rest.starts_with('/') {
+            return None;
+        }
+        if i == 0 {
+            // Not a tag at all; treat the line as text.
+            self.text(content, start);
+        } else if let Some(text) = rest.strip_prefix(' ') {
+            self.text(text, start + i + 1);
+        }
+        None
+    }
+
+    /// Scans the user-visible attributes in the text between `(` and `)`.
+    /// Attribute values are JavaScript expressions.
+    fn attributes(&mut self, text: &str, start: usize) {
+        let bytes = text.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i].is_ascii_whitespace() || bytes[i] == b',' {
+                i += 1;
+                continue;
+            }
+            let name_start = i;
+            while i < bytes.len()
+                && !bytes[i].is_ascii_whitespace()
+                && !matches!(bytes[i], b'=' | b',' | b'!')
+            {
+                i += 1;
+            }
+            let name = &text[name_start..i];
+            if text[i..].starts_with("!=") {
+                i += 2;
+            } else if text[i..].starts_with('=') {
+                i += 1;
+            } else {
+                i = i.max(name_start + 1);
+                continue;
+            }
+            let value_start = i;
+            i = expression_end(text, i);
+            if VISIBLE_ATTRIBUTES
+                .iter()
+                .any(|visible| name.eq_ignore_ascii_case(visible))
+            {
+                let len = self.out.results.len();
+                self.expression(&text[value_start..i], start + value_start);
+                for result in &mut self.out.results[len..] {
+                    result
+                        .context
+                        .property
+                        .get_or_insert_with(|| name.to_string());
+                }
+            }
+        }
+    }
+
+    /// Checks text, scanning `#{}` interpolations as expressions.
+    fn text(&mut self, text: &str, start: usize) {
+        for segment in split_interpolations(text, start, "#{", "}") {
+            match segment {
+                Segment::Static { text, start } => {
+                    self.ctx
+                        .check_markup(text, start as u32, None, &mut self.out);
+                }
+                Segment::Expression { code, start } => self.expression(code, start),
+            }
+        }
+    }
+
+    fn expression(&mut self, code: &str, start: usize) {
+        if code.trim().is_empty() {
+            return;
+        }
+        self.out.extend(js::scan_expression(
+            self.ctx,
+            self.allocator,
+            code,
+            start as u32,
+            SourceType::default(),
+        ));
+    }
+
+    /// Scans a `- code` line. These are often fragments such as `- for (...)`
+    /// whose body is the indented block below, so code that doesn't parse gets
+    /// the token scan instead of a diagnostic.
+    fn code(&mut self, code: &str, start: usize) {
+        let scan = js::scan_script(
+            self.ctx,
+            self.allocator,
+            code,
+            start as u32,
+            SourceType::default(),
+        );
+        if scan.diagnostics.is_empty() {
+            self.out.extend(scan);
+        } else {
+            self.out
+                .results
+                .extend(fallback::scan_tokens(self.ctx, code, start as u32));
+        }
+    }
+
+    fn script(&mut self, start: usize, end: usize) {
+        self.out.extend(js::scan_script(
+            self.ctx,
+            self.allocator,
+            &self.ctx.source_text[start..end],
+            start as u32,
+            SourceType::default(),
+        ));
+    }
+}
+
+/// For a line starting with a keyword, returns the expression after it, which
+/// is empty if it has none, and its offset. Returns `None` for other lines.
+fn keyword_expression(content: &str) -> Option<(usize, &str)> {
+    let keyword_at = |keyword: &str| {
+        content
+            .strip_prefix(keyword)
+            .filter(|rest| rest.is_empty() || rest.starts_with([' ', '\t']))
+    };
+    if let Some(keyword) = EXPRESSION_KEYWORDS.iter().find(|k| keyword_at(k).is_some()) {
+        let offset = keyword.len();
+        return Some((offset, &content[offset..]));
+    }
+    for keyword in ["each", "for"] {
+        if let Some(rest) = keyword_at(keyword) {
+            // `each item, index in items`
+            let offset = rest
+                .find(" in ")
+                .map_or(content.len(), |p| keyword.len() + p + 4);
+            return Some((offset, &content[offset..]));
+        }
+    }
+    if SKIPPED_KEYWORDS.iter().any(|k| keyword_at(k).is_some()) {
+        return Some((content.len(), ""));
+    }
+    None
+}
+
+fn is_name_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_')
+}
+
+/// Given a `(` at `open`, returns the offset of its matching `)`, or the end of
+/// `text`, skipping over quoted strings.
+fn matching_paren(text: &str, open: usize) -> usize {
+    let bytes = text.as_bytes();
+    let mut depth = 0;
+    let mut i = open;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' => depth += 1,
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            quote @ (b'"' | b'\'' | b'`') => i = skip_quoted(bytes, i, quote),
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// The end of an attribute value starting at `start`: the first whitespace or
+/// `,` outside quotes and brackets.
+fn expression_end(text: &str, start: usize) -> usize {
+    let bytes = text.as_bytes();
+    let mut depth = 0usize;
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth = depth.saturating_sub(1),
+            quote @ (b'"' | b'\'' | b'`') => i = skip_quoted(bytes, i, quote),
+            b if depth == 0 && (b.is_ascii_whitespace() || b == b',') => return i,
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+/// Given a quote at `i`, returns the offset of the closing quote.
+fn skip_quoted(bytes: &[u8], mut i: usize, quote: u8) -> usize {
+    i += 1;
+    while i < bytes.len() && bytes[i] != quote {
+        if bytes[i] == b'\\' {
+            i += 1;
+        }
+        i += 1;
+    }
+    i.min(bytes.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::scan_pug;
+    use crate::scanner::file::{covered, scan_test_source};
+    use crate::scanner::FindingKind;
+    use oxc::allocator::Allocator;
+
+    #[test]
+    fn text_attributes_and_code() {
+        let source = "div(title=\"提示\" class=\"x\")\n  p 段落文本\n  | 管道文本\n  p.\n    块文本\n  span= \"表达式\"\n  - var a = \"代码\"\n  p 你好 #{name} 再见\n  //- 注释\n  style.\n    .a { content: \"样式\" }\n";
+        let allocator = Allocator::default();
+        let results = scan_test_source(source, |ctx| scan_pug(ctx, &allocator));
+        let found: Vec<_> = results
+            .iter()
+            .map(|result| (covered(source, result), result.kind))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("提示", FindingKind::StringLiteral),
+                ("段落文本", FindingKind::MarkupText),
+                ("管道文本", FindingKind::MarkupText),
+                ("块文本", FindingKind::MarkupText),
+                ("表达式", FindingKind::StringLiteral),
+                ("代码", FindingKind::StringLiteral),
+                ("你好", FindingKind::MarkupText),
+                ("再见", FindingKind::MarkupText),
+            ]
+        );
+        assert_eq!(results[0].context.property.as_deref(), Some("title"));
+    }
+}
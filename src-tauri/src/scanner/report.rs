@@ -0,0 +1,331 @@
+use super::{FileType, Script};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ScanResult {
+    #[serde(rename = "filePath")]
+    pub file_path: String,
+    /// Where the Chinese starts: the first Han character as written in the
+    /// source, an escape such as `\u4e2d` included.
+    pub line: usize,
+    pub column: usize,
+    /// Just past the last Han character, so `line:column` to
+    /// `endLine:endColumn` covers the Chinese in the finding.
+    #[serde(rename = "endLine")]
+    pub end_line: usize,
+    #[serde(rename = "endColumn")]
+    pub end_column: usize,
+    /// The same range as byte offsets into the file.
+    #[serde(rename = "startOffset")]
+    pub start_offset: usize,
+    #[serde(rename = "endOffset")]
+    pub end_offset: usize,
+    pub text: String,
+    /// Each run of Chinese in `text`, with any CJK punctuation next to it,
+    /// in order. The range above runs from the first to the end of the last.
+    pub matches: Vec<MatchRange>,
+    /// The configured scripts found in `text`, e.g. `["han"]`.
+    pub scripts: Vec<Script>,
+    pub confidence: Confidence,
+    pub kind: FindingKind,
+    /// Where in the code the finding is, as far as the extractor can tell.
+    #[serde(skip_serializing_if = "FindingContext::is_empty")]
+    pub context: FindingContext,
+    /// Key path of a resource string, e.g. `common.button.save`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// Locale of the resource file the finding is in, e.g. `en`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// The nearest config file applied to the finding's file, relative to
+    /// the root.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<String>,
+}
+
+impl ScanResult {
+    /// One finding per entry in `matches`, each with that run's range and
+    /// text and the rest copied. A finding with no matches is kept as it
+    /// is.
+    pub(crate) fn split(self) -> Vec<ScanResult> {
+        if self.matches.is_empty() {
+            return vec![self];
+        }
+        self.matches
+            .iter()
+            .map(|found| ScanResult {
+                line: found.line,
+                column: found.column,
+                end_line: found.end_line,
+                end_column: found.end_column,
+                start_offset: found.start_offset,
+                end_offset: found.end_offset,
+                text: found.text.clone(),
+                matches: vec![found.clone()],
+                ..self.clone()
+            })
+            .collect()
+    }
+}
+
+/// One run of Chinese within a finding.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MatchRange {
+    /// The run as decoded, escapes resolved.
+    pub text: String,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub start_offset: usize,
+    pub end_offset: usize,
+}
+
+/// How sure the scanner is that a finding is a real string or text node.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Confidence {
+    /// Found by a format-aware extractor: oxc, tree-sitter, the markup
+    /// tokenizer or a resource file reader, even if it had to recover from
+    /// errors.
+    High,
+    /// Found by the token-level fallback, on a file oxc could not parse or a
+    /// script that isn't valid JavaScript before its template is rendered.
+    Low,
+}
+
+/// The kind of node a finding was taken from.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FindingKind {
+    /// A quoted string in a script or backend source, including Python
+    /// f-strings and Kotlin string templates.
+    StringLiteral,
+    /// One of the static parts of a template literal.
+    TemplateLiteral,
+    /// A JSX text node.
+    JsxText,
+    /// A quoted JSX attribute value, `title="..."`.
+    JsxAttribute,
+    /// A text node in an HTML, Vue, Svelte, Astro, mini-program or server
+    /// template.
+    MarkupText,
+    /// A static attribute value in one of those templates.
+    MarkupAttribute,
+    /// A value in a locale resource or mini-program config file.
+    Resource,
+    /// A comment, only reported when [`ScanOptions::comments`] is set.
+    ///
+    /// [`ScanOptions::comments`]: super::ScanOptions::comments
+    Comment,
+    /// A declared name: variable, function, class, parameter or destructured
+    /// binding. This and the kinds below are only reported when
+    /// [`ScanOptions::identifiers`] is set.
+    ///
+    /// [`ScanOptions::identifiers`]: super::ScanOptions::identifiers
+    Identifier,
+    /// An object, class, interface or enum member name written without quotes.
+    PropertyKey,
+    /// A name in an `import` statement, imported or local.
+    ImportSpecifier,
+    /// The file's path relative to the root; reported at line 1, column 1.
+    FilePath,
+}
+
+/// The code around a finding in a script. Each part is only set when there is one.
+#[derive(Debug, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct FindingContext {
+    /// Name of the innermost named function, method (`Class.method`) or
+    /// component the finding is in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+    /// The function called with the string as an argument, e.g. `console.log`
+    /// or `t`, or the tag of a tagged template.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callee: Option<String>,
+    /// The JSX or markup attribute, object property, class field or enum
+    /// member the string is the value of.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub property: Option<String>,
+}
+
+impl FindingContext {
+    pub fn is_empty(&self) -> bool {
+        self.function.is_none() && self.callee.is_none() && self.property.is_none()
+    }
+}
+
+/// Everything a scan produced.
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanReport {
+    pub results: Vec<ScanResult>,
+    /// Number of findings containing each script; a finding mixing two
+    /// counts towards both.
+    pub script_counts: BTreeMap<Script, usize>,
+    /// Config files read and applied during the scan, relative to the root.
+    pub configs: Vec<String>,
+    /// Number of source files the scan processed.
+    pub files_scanned: usize,
+    /// Files that could not be read or parsed, so their findings are missing.
+    pub diagnostics: Vec<ScanDiagnostic>,
+    /// The scan was stopped early; `results` only covers part of the tree.
+    pub cancelled: bool,
+}
+
+/// What [`Scanner::dry_run`] found: every file the scan would read, and
+/// every file or directory it would leave out.
+///
+/// [`Scanner::dry_run`]: super::Scanner::dry_run
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DryRunReport {
+    /// Sorted by path.
+    pub files: Vec<PlannedFile>,
+    /// Config files read and applied, relative to the root.
+    pub configs: Vec<String>,
+    pub diagnostics: Vec<ScanDiagnostic>,
+}
+
+/// A file or directory in a dry run.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedFile {
+    /// Path relative to the root.
+    pub file_path: String,
+    /// A directory left out with everything in it.
+    pub is_dir: bool,
+    /// How the file would be scanned; `None` when it's skipped.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_type: Option<FileType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    /// Why the file or directory is left out; `None` when it's scanned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<SkipReason>,
+    /// The nearest config file that applies, relative to the root.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub config: Option<String>,
+}
+
+/// Why a file or directory is left out of a scan, in order of precedence.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SkipReason {
+    /// Matched by an exclude glob, from the options or a config file.
+    Excluded,
+    /// Matched by `.gitignore`, `.ignore`, git's global excludes or a
+    /// `.chinesescanignore` file.
+    Ignored,
+    /// A `.git`, `.hg` or `.svn` directory, which is never walked.
+    VersionControl,
+    /// A hidden file or directory, with [`ScanOptions::skip_hidden`] set.
+    ///
+    /// [`ScanOptions::skip_hidden`]: super::ScanOptions::skip_hidden
+    Hidden,
+    /// A directory at [`ScanOptions::max_depth`], which isn't walked into.
+    ///
+    /// [`ScanOptions::max_depth`]: super::ScanOptions::max_depth
+    TooDeep,
+    /// A symbolic link, without [`ScanOptions::follow_links`] set.
+    ///
+    /// [`ScanOptions::follow_links`]: super::ScanOptions::follow_links
+    Symlink,
+    /// Not a regular file, e.g. a socket or a broken link.
+    NotAFile,
+    /// There are include globs and the file matches none of them.
+    NotIncluded,
+    /// A `.d.ts` file, with [`ScanOptions::skip_declarations`] set.
+    ///
+    /// [`ScanOptions::skip_declarations`]: super::ScanOptions::skip_declarations
+    Declaration,
+    /// No scanner handles the file's type.
+    UnknownType,
+    /// Resources of a locale whose files may contain Chinese.
+    ChineseLocale,
+    /// Larger than [`ScanOptions::max_file_size`].
+    ///
+    /// [`ScanOptions::max_file_size`]: super::ScanOptions::max_file_size
+    TooLarge,
+}
+
+impl SkipReason {
+    pub fn description(self) -> &'static str {
+        match self {
+            SkipReason::Excluded => "matches an exclude glob",
+            SkipReason::Ignored => "ignored by .gitignore, .ignore or .chinesescanignore",
+            SkipReason::VersionControl => "version control directory",
+            SkipReason::Hidden => "hidden",
+            SkipReason::TooDeep => "deeper than the maximum depth",
+            SkipReason::Symlink => "symbolic link, not followed",
+            SkipReason::NotAFile => "not a regular file",
+            SkipReason::NotIncluded => "matches no include glob",
+            SkipReason::Declaration => "declaration file",
+            SkipReason::UnknownType => "no scanner for this file type",
+            SkipReason::ChineseLocale => "resources of a Chinese locale",
+            SkipReason::TooLarge => "larger than the maximum file size",
+        }
+    }
+}
+
+/// Why a file, or part of the tree, could not be scanned.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DiagnosticKind {
+    /// The walker could not enter a directory or stat an entry.
+    Walk,
+    /// The file could not be read, e.g. permissions or invalid UTF-8.
+    Read,
+    /// oxc reported a syntax error.
+    Parse,
+    /// A config file below the root could not be read or parsed; its
+    /// subtree was scanned with the settings above it.
+    Config,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanDiagnostic {
+    pub file_path: String,
+    pub kind: DiagnosticKind,
+    pub message: String,
+    /// Position of a parse error, when oxc attached one.
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::scanner::file::scan_test_source;
+    use crate::scanner::properties::scan_properties;
+
+    #[test]
+    fn split_gives_each_run_its_range() {
+        let source = "one = 保存 ok\nmany = 点击“保存”按钮 to save, or 取消。\n";
+        let split: Vec<_> = scan_test_source(source, scan_properties)
+            .into_iter()
+            .flat_map(|result| result.split())
+            .collect();
+        let runs: Vec<_> = split
+            .iter()
+            .map(|result| {
+                (
+                    result.key.as_deref(),
+                    result.text.as_str(),
+                    &source[result.start_offset..result.end_offset],
+                )
+            })
+            .collect();
+        assert_eq!(
+            runs,
+            [
+                (Some("one"), "保存", "保存"),
+                (Some("many"), "点击“保存”按钮", "点击“保存”按钮"),
+                (Some("many"), "取消。", "取消。"),
+            ]
+        );
+        assert!(split.iter().all(|result| result.matches.len() == 1));
+    }
+}
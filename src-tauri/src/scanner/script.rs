@@ -0,0 +1,168 @@
+//! The writing systems a scan looks for.
+
+use regex::Regex;
+use serde::{Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// A set of characters to look for. Han, the default, is what the scanner is
+/// named for; the others let the same checks find Japanese, Korean or any
+/// non-ASCII text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Script {
+    /// Chinese characters, also used in Japanese kanji and Korean hanja.
+    Han,
+    Hiragana,
+    /// Katakana, including the `ー` length mark.
+    Katakana,
+    /// Korean syllables and jamo.
+    Hangul,
+    /// Any character outside ASCII other than whitespace and invisible
+    /// format characters.
+    NonAscii,
+    /// Matches of the pattern given to [`ScanOptions::pattern`].
+    ///
+    /// [`ScanOptions::pattern`]: super::ScanOptions::pattern
+    Custom,
+}
+
+impl Script {
+    /// The scripts that can be named; [`Script::Custom`] comes from a pattern.
+    pub const ALL: [Script; 5] = [
+        Script::Han,
+        Script::Hiragana,
+        Script::Katakana,
+        Script::Hangul,
+        Script::NonAscii,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Script::Han => "han",
+            Script::Hiragana => "hiragana",
+            Script::Katakana => "katakana",
+            Script::Hangul => "hangul",
+            Script::NonAscii => "non-ascii",
+            Script::Custom => "custom",
+        }
+    }
+
+    fn pattern(self) -> Option<&'static str> {
+        match self {
+            Script::Han => Some(r"\p{Han}"),
+            Script::Hiragana => Some(r"\p{Hiragana}"),
+            Script::Katakana => Some(r"[\p{Katakana}\x{30fc}\x{ff70}]"),
+            Script::Hangul => Some(r"\p{Hangul}"),
+            Script::NonAscii => Some(r"[^\p{ASCII}\s\p{Cf}]"),
+            Script::Custom => None,
+        }
+    }
+}
+
+impl fmt::Display for Script {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Serialized as [`Script::name`], the name `--script` and config files take.
+impl Serialize for Script {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl FromStr for Script {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, String> {
+        let name = name.trim();
+        Script::ALL
+            .into_iter()
+            .find(|script| script.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                format!(
+                    "Unknown script '{}', expected one of: han, hiragana, katakana, hangul, non-ascii",
+                    name
+                )
+            })
+    }
+}
+
+/// The compiled scripts of one scan: a regex matching any of them, used to
+/// find text, and one per script to tell which a finding contains.
+pub(crate) struct ScriptSet {
+    pub(crate) regex: Regex,
+    scripts: Vec<(Script, Regex)>,
+}
+
+impl ScriptSet {
+    pub(crate) fn new(scripts: &[Script], pattern: Option<&str>) -> Result<Self, String> {
+        let mut patterns: Vec<(Script, String)> = Vec::new();
+        for &script in scripts {
+            if let Some(pattern) = script.pattern() {
+                if !patterns.iter().any(|(known, _)| *known == script) {
+                    patterns.push((script, pattern.to_string()));
+                }
+            }
+        }
+        if let Some(pattern) = pattern {
+            let regex =
+                Regex::new(pattern).map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))?;
+            // A pattern that can match nothing would flag every string.
+            if regex.is_match("") {
+                return Err(format!("Pattern '{}' matches the empty string", pattern));
+            }
+            patterns.push((Script::Custom, format!("(?:{})", pattern)));
+        }
+        if patterns.is_empty() {
+            return Err("No scripts or pattern to scan for".to_string());
+        }
+        let combined: Vec<&str> = patterns
+            .iter()
+            .map(|(_, pattern)| pattern.as_str())
+            .collect();
+        let regex = Regex::new(&combined.join("|")).map_err(|e| e.to_string())?;
+        let scripts = patterns
+            .into_iter()
+            .map(|(script, pattern)| Ok((script, Regex::new(&pattern).map_err(|e| e.to_string())?)))
+            .collect::<Result<_, String>>()?;
+        Ok(Self { regex, scripts })
+    }
+
+    /// The scripts found in `text`, in the order they were configured.
+    pub(crate) fn scripts_in(&self, text: &str) -> Vec<Script> {
+        self.scripts
+            .iter()
+            .filter(|(_, regex)| regex.is_match(text))
+            .map(|(script, _)| *script)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Script, ScriptSet};
+
+    #[test]
+    fn rejects_patterns_matching_nothing() {
+        for pattern in ["", "x*", "(?:)|x"] {
+            let error = ScriptSet::new(&[], Some(pattern)).err();
+            assert_eq!(
+                error,
+                Some(format!("Pattern '{}' matches the empty string", pattern))
+            );
+        }
+        assert!(ScriptSet::new(&[Script::Han], Some("x+")).is_ok());
+    }
+
+    #[test]
+    fn reports_the_scripts_found() {
+        let scripts = ScriptSet::new(&[Script::Han, Script::Katakana], Some("[À-ÿ]")).unwrap();
+        assert_eq!(
+            scripts.scripts_in("カタカナ 中文 café"),
+            [Script::Han, Script::Katakana, Script::Custom]
+        );
+        assert!(scripts.scripts_in("plain").is_empty());
+    }
+}
@@ -0,0 +1,50 @@
+//! Splits text into the runs of Chinese in it.
+//!
+//! A segment is a run of characters matched by the Chinese regex, extended
+//! over any CJK punctuation next to it, so `你好，世界！` is one segment while
+//! `保存 to 服务器` is two.
+
+use regex::Regex;
+use std::ops::Range;
+
+/// Byte ranges of the segments in `text`, in order.
+pub(crate) fn segments(chinese_regex: &Regex, text: &str) -> Vec<Range<usize>> {
+    let mut segments: Vec<Range<usize>> = Vec::new();
+    for found in chinese_regex.find_iter(text) {
+        let start = text[..found.start()]
+            .char_indices()
+            .rev()
+            .take_while(|&(_, c)| is_cjk_punctuation(c))
+            .last()
+            .map_or(found.start(), |(i, _)| i);
+        let end = found.end()
+            + text[found.end()..]
+                .chars()
+                .take_while(|&c| is_cjk_punctuation(c))
+                .map(char::len_utf8)
+                .sum::<usize>();
+        match segments.last_mut() {
+            Some(last) if last.end >= start => last.end = last.end.max(end),
+            _ => segments.push(start..end),
+        }
+    }
+    segments
+}
+
+/// Full-width and CJK punctuation, brackets and quotes, e.g. `，。、「」《》“”…`.
+fn is_cjk_punctuation(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3001}'..='\u{303f}'
+            | '\u{ff01}'..='\u{ff0f}'
+            | '\u{ff1a}'..='\u{ff20}'
+            | '\u{ff3b}'..='\u{ff40}'
+            | '\u{ff5b}'..='\u{ff65}'
+            | '\u{fe10}'..='\u{fe1f}'
+            | '\u{fe30}'..='\u{fe4f}'
+            | '\u{2018}'..='\u{201f}'
+            | '\u{2014}'
+            | '\u{2026}'
+            | '\u{00b7}'
+    )
+}
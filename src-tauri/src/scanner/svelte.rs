@@ -0,0 +1,55 @@
+//! Svelte components. The whole file is markup; `<script>` and
+//! `<script context="module">` blocks go through the oxc visitor, and `{...}`
+//! tags, including logic blocks such as `{#if}` and `{#each}`, are scanned as
+//! expressions.
+
+use super::component::{scan_markup, Dialect};
+use super::file::{FileContext, FileScan};
+use oxc::allocator::Allocator;
+use oxc::span::SourceType;
+
+pub(crate) fn scan_svelte(ctx: &FileContext, allocator: &Allocator) -> FileScan {
+    let dialect = Dialect {
+        delimiters: ("{", "}"),
+        script_tags: &["script"],
+        default_script: SourceType::default().with_module(true),
+        // Markup expressions may use TypeScript syntax when the script does.
+        expression: SourceType::default()
+            .with_module(true)
+            .with_typescript(true),
+        logic_blocks: true,
+        interpolate_quoted: true,
+    };
+    let mut out = FileScan::default();
+    scan_markup(ctx, allocator, 0, ctx.source_text.len(), &dialect, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::scan_svelte;
+    use crate::scanner::file::{covered, scan_test_source};
+    use crate::scanner::FindingKind;
+    use oxc::allocator::Allocator;
+
+    #[test]
+    fn markup_blocks_and_script() {
+        let source = "<script>\n  let name = \"世界\";\n</script>\n<h1 title=\"标题\">你好 {name}</h1>\n{#if ok}\n  <p>{ok ? \"成功\" : \"失败\"}</p>\n{/if}\n";
+        let allocator = Allocator::default();
+        let results = scan_test_source(source, |ctx| scan_svelte(ctx, &allocator));
+        let found: Vec<_> = results
+            .iter()
+            .map(|result| (covered(source, result), result.kind))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("世界", FindingKind::StringLiteral),
+                ("标题", FindingKind::MarkupAttribute),
+                ("你好", FindingKind::MarkupText),
+                ("成功", FindingKind::StringLiteral),
+                ("失败", FindingKind::StringLiteral),
+            ]
+        );
+    }
+}
@@ -0,0 +1,427 @@
+//! Backend languages parsed with tree-sitter grammars: Java, Go, Python and
+//! Kotlin.
+//!
+//! String literals are reported with their delimiters and prefixes removed
+//! and the language's escapes decoded, so `"\u4e2d\u6587"` is found as
+//! `中文`, its range covering the escapes; raw strings are taken as written.
+//! The parts of a Python f-string or a Kotlin string template around `{}` or
+//! `$name` are reported separately, like a template literal's quasis, and
+//! strings inside the braces on their own.
+//! Comments, and Python docstrings, are only reported when
+//! [`ScanOptions::comments`] is set.
+//!
+//! [`ScanOptions::comments`]: super::ScanOptions::comments
+
+use super::file::{FileContext, FileScan};
+use super::{Confidence, DiagnosticKind, FindingKind};
+use tree_sitter::{Language, Node, Parser};
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Grammar {
+    Java,
+    Go,
+    Python,
+    Kotlin,
+}
+
+impl Grammar {
+    fn language(self) -> Language {
+        match self {
+            Grammar::Java => tree_sitter_java::LANGUAGE.into(),
+            Grammar::Go => tree_sitter_go::LANGUAGE.into(),
+            Grammar::Python => tree_sitter_python::LANGUAGE.into(),
+            Grammar::Kotlin => tree_sitter_kotlin_ng::LANGUAGE.into(),
+        }
+    }
+
+    fn is_string(self, kind: &str) -> bool {
+        match self {
+            Grammar::Java => matches!(kind, "string_literal" | "character_literal"),
+            Grammar::Go => matches!(
+                kind,
+                "interpreted_string_literal" | "raw_string_literal" | "rune_literal"
+            ),
+            Grammar::Python => kind == "string",
+            Grammar::Kotlin => matches!(
+                kind,
+                "string_literal" | "multiline_string_literal" | "character_literal"
+            ),
+        }
+    }
+
+    fn is_comment(self, kind: &str) -> bool {
+        matches!(kind, "comment" | "line_comment" | "block_comment")
+    }
+}
+
+pub(crate) fn scan_source(ctx: &FileContext, grammar: Grammar) -> FileScan {
+    let mut out = FileScan::default();
+    let mut parser = Parser::new();
+    if let Err(err) = parser.set_language(&grammar.language()) {
+        out.diagnostics
+            .push(ctx.diagnostic(DiagnosticKind::Parse, err.to_string(), None));
+        return out;
+    }
+    let Some(tree) = parser.parse(ctx.source_text, None) else {
+        out.diagnostics.push(ctx.diagnostic(
+            DiagnosticKind::Parse,
+            "The parser gave up on this file".to_string(),
+            None,
+        ));
+        return out;
+    };
+
+    // Whatever tree-sitter recovers around a syntax error is still visited.
+    let root = tree.root_node();
+    if root.has_error() {
+        if let Some(error) = first_error(root) {
+            let message = if error.is_missing() {
+                format!("Missing {}", error.kind())
+            } else {
+                "Syntax error".to_string()
+            };
+            out.diagnostics.push(ctx.diagnostic(
+                DiagnosticKind::Parse,
+                message,
+                Some(error.start_byte() as u32),
+            ));
+        }
+    }
+
+    let mut cursor = tree.walk();
+    loop {
+        let node = cursor.node();
+        let kind = node.kind();
+        if grammar.is_string(kind) {
+            // A bare string statement in Python is a docstring.
+            let docstring = matches!(grammar, Grammar::Python)
+                && node
+                    .parent()
+                    .is_some_and(|parent| parent.kind() == "expression_statement");
+            if !docstring {
+                check_string(ctx, node, grammar, &mut out);
+            } else if ctx.comments {
+                let (start, end) = string_content(ctx, node);
+                ctx.check_comment(&ctx.source_text[start..end], start as u32, &mut out);
+            }
+        } else if grammar.is_comment(kind) && ctx.comments {
+            check_comment(ctx, node, &mut out);
+        }
+
+        // Depth-first, in source order.
+        if cursor.goto_first_child() {
+            continue;
+        }
+        while !cursor.goto_next_sibling() {
+            if !cursor.goto_parent() {
+                return out;
+            }
+        }
+    }
+}
+
+fn first_error(node: Node) -> Option<Node> {
+    if node.is_error() || node.is_missing() {
+        return Some(node);
+    }
+    let mut cursor = node.walk();
+    let children: Vec<_> = node.children(&mut cursor).collect();
+    children
+        .into_iter()
+        .filter(|child| child.has_error())
+        .find_map(first_error)
+}
+
+/// Checks a string literal's content, split around f-string and string
+/// template interpolations.
+fn check_string(ctx: &FileContext, node: Node, grammar: Grammar, out: &mut FileScan) {
+    let (content_start, content_end) = string_content(ctx, node);
+    // Go backquoted strings, Kotlin `"""` strings and Python `r` strings
+    // have no escapes.
+    let prefix = &ctx.source_text[node.start_byte()..content_start];
+    let escapes = match node.kind() {
+        "raw_string_literal" | "multiline_string_literal" => None,
+        _ if prefix.contains(['r', 'R']) => None,
+        _ => Some(grammar),
+    };
+    let mut piece_start = content_start;
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        let interpolation = match child.kind() {
+            "interpolation" => Some(child.end_byte()),
+            // The Kotlin grammar leaves `$name` as string content, with the
+            // `$` on its own.
+            "string_content" if child.utf8_text(ctx.source_text.as_bytes()) == Ok("$") => {
+                template_name_end(ctx.source_text, child.end_byte())
+            }
+            _ => None,
+        };
+        if let Some(end) = interpolation {
+            check_piece(ctx, piece_start, child.start_byte(), escapes, out);
+            piece_start = end;
+        }
+    }
+    check_piece(ctx, piece_start, content_end, escapes, out);
+}
+
+/// The end of the identifier of a `$name` template starting at `start`, if
+/// there is one there.
+fn template_name_end(source: &str, start: usize) -> Option<usize> {
+    let rest = &source[start..];
+    if !rest
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+    {
+        return None;
+    }
+    let len = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+    Some(start + len)
+}
+
+/// The byte range of a string literal without its prefix and quotes.
+fn string_content(ctx: &FileContext, node: Node) -> (usize, usize) {
+    let (start, end) = (node.start_byte(), node.end_byte());
+    let text = &ctx.source_text[start..end];
+    // Python prefixes such as `f`, `r` or `rb`, then one or three quotes.
+    let prefix = text.len()
+        - text
+            .trim_start_matches(|c: char| c.is_ascii_alphabetic())
+            .len();
+    let delimiter = if text[prefix..].starts_with("\"\"\"") || text[prefix..].starts_with("'''") {
+        3
+    } else {
+        1
+    };
+    let content_start = (start + prefix + delimiter).min(end);
+    (
+        content_start,
+        end.saturating_sub(delimiter).max(content_start),
+    )
+}
+
+/// Checks the source from `start` to `end`, decoding `escapes`' escape
+/// sequences if it has them.
+fn check_piece(
+    ctx: &FileContext,
+    start: usize,
+    end: usize,
+    escapes: Option<Grammar>,
+    out: &mut FileScan,
+) {
+    if start >= end {
+        return;
+    }
+    let raw = &ctx.source_text[start..end];
+    let Some(grammar) = escapes.filter(|_| raw.contains('\\')) else {
+        ctx.check_text(
+            raw,
+            start as u32,
+            Confidence::High,
+            FindingKind::StringLiteral,
+            out,
+        );
+        return;
+    };
+    let value = unescape(raw, grammar);
+    let segments = ctx.raw_segments(&value, raw, start, true);
+    if !segments.is_empty() {
+        out.results.push(ctx.result(
+            segments,
+            value.trim(),
+            Confidence::High,
+            FindingKind::StringLiteral,
+        ));
+    }
+}
+
+/// What an escape sequence stands for.
+enum Escaped {
+    Char(char),
+    /// A byte of UTF-8, from Go's `\xHH` and octal escapes.
+    Byte(u8),
+    /// A line continuation.
+    Nothing,
+}
+
+/// Decodes the escapes in a string literal's content. One the language
+/// doesn't have, or that doesn't decode, is kept as written.
+fn unescape(raw: &str, grammar: Grammar) -> String {
+    let mut value = Vec::with_capacity(raw.len());
+    let mut rest = raw;
+    while let Some(backslash) = rest.find('\\') {
+        value.extend_from_slice(&rest.as_bytes()[..backslash]);
+        rest = &rest[backslash..];
+        let (escaped, len) = escape(rest, grammar).unwrap_or((Escaped::Char('\\'), 1));
+        match escaped {
+            Escaped::Char(c) => value.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes()),
+            Escaped::Byte(b) => value.push(b),
+            Escaped::Nothing => {}
+        }
+        rest = &rest[len..];
+    }
+    value.extend_from_slice(rest.as_bytes());
+    String::from_utf8_lossy(&value).into_owned()
+}
+
+/// The escape sequence at the start of `raw`, which starts with a backslash,
+/// and its length.
+fn escape(raw: &str, grammar: Grammar) -> Option<(Escaped, usize)> {
+    let next = raw[1..].chars().next()?;
+    let hex = |from: usize, len: usize| {
+        let digits = raw.get(from..from + len)?;
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        u32::from_str_radix(digits, 16).ok()
+    };
+    let simple = match next {
+        'n' => Some('\n'),
+        't' => Some('\t'),
+        'r' => Some('\r'),
+        'b' => Some('\u{8}'),
+        '\\' | '"' | '\'' => Some(next),
+        'f' if !matches!(grammar, Grammar::Kotlin) => Some('\u{c}'),
+        'a' if matches!(grammar, Grammar::Go | Grammar::Python) => Some('\u{7}'),
+        'v' if matches!(grammar, Grammar::Go | Grammar::Python) => Some('\u{b}'),
+        's' if matches!(grammar, Grammar::Java) => Some(' '),
+        '$' if matches!(grammar, Grammar::Kotlin) => Some('$'),
+        _ => None,
+    };
+    if let Some(c) = simple {
+        return Some((Escaped::Char(c), 1 + next.len_utf8()));
+    }
+    match next {
+        // Python strings and Java text blocks continue over an escaped
+        // line break.
+        '\n' | '\r' if matches!(grammar, Grammar::Python | Grammar::Java) => {
+            let len = if raw[1..].starts_with("\r\n") { 3 } else { 2 };
+            Some((Escaped::Nothing, len))
+        }
+        'u' => {
+            // Java allows any number of `u`s.
+            let us = raw[1..].len() - raw[1..].trim_start_matches('u').len();
+            let unit = hex(1 + us, 4)?;
+            let len = 1 + us + 4;
+            if let Some(c) = char::from_u32(unit) {
+                return Some((Escaped::Char(c), len));
+            }
+            // A surrogate pair written as two escapes.
+            let low = raw[len..].strip_prefix("\\u").and(hex(len + 2, 4))?;
+            let combined =
+                0x10000 + ((unit.checked_sub(0xd800)?) << 10) + low.checked_sub(0xdc00)?;
+            char::from_u32(combined).map(|c| (Escaped::Char(c), len + 6))
+        }
+        'U' if matches!(grammar, Grammar::Go | Grammar::Python) => {
+            char::from_u32(hex(2, 8)?).map(|c| (Escaped::Char(c), 10))
+        }
+        'x' if matches!(grammar, Grammar::Go) => Some((Escaped::Byte(hex(2, 2)? as u8), 4)),
+        'x' if matches!(grammar, Grammar::Python) => {
+            char::from_u32(hex(2, 2)?).map(|c| (Escaped::Char(c), 4))
+        }
+        '0'..='7' if !matches!(grammar, Grammar::Kotlin) => {
+            // Go takes exactly three digits, as a byte; Java and Python up to
+            // three, as a character.
+            let max = raw[1..]
+                .bytes()
+                .take(3)
+                .take_while(|b| (b'0'..=b'7').contains(b))
+                .count();
+            let len = if matches!(grammar, Grammar::Go) {
+                3
+            } else {
+                max
+            };
+            if max < len {
+                return None;
+            }
+            let value = u32::from_str_radix(&raw[1..1 + len], 8).ok()?;
+            if matches!(grammar, Grammar::Go) {
+                Some((Escaped::Byte(u8::try_from(value).ok()?), 1 + len))
+            } else {
+                char::from_u32(value).map(|c| (Escaped::Char(c), 1 + len))
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Checks a comment without its `//`, `#` or `/* */` markers.
+fn check_comment(ctx: &FileContext, node: Node, out: &mut FileScan) {
+    let (mut start, mut end) = (node.start_byte(), node.end_byte());
+    let text = &ctx.source_text[start..end];
+    if text.starts_with("/*") {
+        start += 2;
+        if text.len() >= 4 && text.ends_with("*/") {
+            end = (end - 2).max(start);
+        }
+    } else if text.starts_with("//") {
+        start += 2;
+    } else if text.starts_with('#') {
+        start += 1;
+    }
+    ctx.check_comment(&ctx.source_text[start..end], start as u32, out);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{scan_source, Grammar};
+    use crate::scanner::file::{covered, scan_test_source};
+
+    /// Each finding's text and the source its range covers.
+    fn found(source: &str, grammar: Grammar) -> Vec<(String, String)> {
+        scan_test_source(source, |ctx| scan_source(ctx, grammar))
+            .iter()
+            .map(|result| (result.text.clone(), covered(source, result).to_string()))
+            .collect()
+    }
+
+    fn pairs(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+        expected
+            .iter()
+            .map(|(text, raw)| (text.to_string(), raw.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn java_escapes() {
+        let source = "class A { String a = \"\\u4e2d\\u6587\"; String b = \"ok\\t中\\uuu6587\"; char c = '\\u4e2d'; }\n";
+        assert_eq!(
+            found(source, Grammar::Java),
+            pairs(&[
+                ("中文", "\\u4e2d\\u6587"),
+                ("ok\t中文", "中\\uuu6587"),
+                ("中", "\\u4e2d"),
+            ])
+        );
+    }
+
+    #[test]
+    fn go_escapes_and_raw_strings() {
+        let source = "package a\nvar a = \"\\u4e2d\\U00006587\"\nvar b = `\\u4e2d`\nvar c = \"\\xe4\\xb8\\xad\"\n";
+        assert_eq!(
+            found(source, Grammar::Go),
+            pairs(&[("中文", "\\u4e2d\\U00006587"), ("中", "\\xe4\\xb8\\xad")])
+        );
+    }
+
+    #[test]
+    fn python_escapes_and_raw_strings() {
+        let source = "a = \"\\u4e2d\\N{DIGIT ONE}\"\nb = r\"\\u4e2d\"\nc = f\"\\x41{x}\\u6587\"\n";
+        assert_eq!(
+            found(source, Grammar::Python),
+            pairs(&[("中\\N{DIGIT ONE}", "\\u4e2d"), ("文", "\\u6587")])
+        );
+    }
+
+    #[test]
+    fn kotlin_escapes_around_templates() {
+        let source = "val a = \"\\u4e2d\\$ \\u6587$name\"\nval b = \"\"\"\\u4e2d\"\"\"\n";
+        assert_eq!(
+            found(source, Grammar::Kotlin),
+            pairs(&[("中$ 文", "\\u4e2d\\$ \\u6587")])
+        );
+    }
+}
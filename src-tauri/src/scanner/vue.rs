@@ -0,0 +1,179 @@
+//! Vue single-file components.
+//!
+//! `<script>` and `<script setup>` blocks go through the oxc visitor. In the
+//! `<template>`, text nodes, static attribute values, `{{ }}` interpolations
+//! and bound attributes (`:title`, `v-bind:`, `@click`, `v-if`, `#slot`) are
+//! scanned, the latter two as JavaScript expressions. `<style>` and custom
+//! blocks such as `<i18n>` are skipped.
+
+use super::file::{FileContext, FileScan};
+use super::js;
+use super::markup::{attribute_value, split_interpolations, Attribute, Segment, Token, Tokenizer};
+use oxc::allocator::Allocator;
+use oxc::span::SourceType;
+
+pub(crate) fn scan_vue(ctx: &FileContext, allocator: &Allocator) -> FileScan {
+    let source = ctx.source_text;
+    let mut out = FileScan::default();
+    let mut tokenizer = Tokenizer::new(source, 0, source.len()).with_interpolation("{{", "}}");
+    // Template expressions may use TypeScript syntax when the script does.
+    let expression_type = SourceType::default()
+        .with_module(true)
+        .with_typescript(true);
+
+    while let Some(token) = tokenizer.next() {
+        let Token::StartTag {
+            name,
+            attributes,
+            self_closing: false,
+        } = token
+        else {
+            continue;
+        };
+
+        match name {
+            "script" => {
+                let (code, start) = tokenizer.raw_text("script");
+                let source_type = js::lang_source_type(
+                    lang(&attributes),
+                    SourceType::default().with_module(true),
+                );
+                out.extend(js::scan_script(
+                    ctx,
+                    allocator,
+                    code,
+                    start as u32,
+                    source_type,
+                ));
+            }
+            "template" if matches!(lang(&attributes), None | Some("html")) => {
+                scan_template(ctx, allocator, &mut tokenizer, expression_type, &mut out);
+            }
+            // Pug templates, styles and custom blocks.
+            _ => {
+                tokenizer.raw_text(name);
+            }
+        }
+    }
+
+    out
+}
+
+/// Scans tokens up to the `</template>` closing the top-level block.
+fn scan_template(
+    ctx: &FileContext,
+    allocator: &Allocator,
+    tokenizer: &mut Tokenizer,
+    expression_type: SourceType,
+    out: &mut FileScan,
+) {
+    let mut depth = 1;
+    for token in tokenizer.by_ref() {
+        match token {
+            Token::StartTag {
+                name,
+                attributes,
+                self_closing,
+            } => {
+                if name == "template" && !self_closing {
+                    depth += 1;
+                }
+                for attribute in &attributes {
+                    scan_attribute(ctx, allocator, attribute, expression_type, out);
+                }
+            }
+            Token::EndTag { name: "template" } => {
+                depth -= 1;
+                if depth == 0 {
+                    return;
+                }
+            }
+            Token::Text { text, start } => {
+                for segment in split_interpolations(text, start, "{{", "}}") {
+                    match segment {
+                        Segment::Static { text, start } => {
+                            ctx.check_markup(text, start as u32, None, out);
+                        }
+                        Segment::Expression { code, start } => {
+                            out.extend(js::scan_expression(
+                                ctx,
+                                allocator,
+                                code,
+                                start as u32,
+                                expression_type,
+                            ));
+                        }
+                    }
+                }
+            }
+            Token::EndTag { .. } | Token::Comment => {}
+        }
+    }
+}
+
+fn scan_attribute(
+    ctx: &FileContext,
+    allocator: &Allocator,
+    attribute: &Attribute,
+    expression_type: SourceType,
+    out: &mut FileScan,
+) {
+    let Some(value) = &attribute.value else {
+        return;
+    };
+    if is_directive(attribute.name) {
+        out.extend(js::scan_expression(
+            ctx,
+            allocator,
+            value.text,
+            value.start as u32,
+            expression_type,
+        ));
+    } else {
+        ctx.check_markup(value.text, value.start as u32, Some(attribute.name), out);
+    }
+}
+
+fn lang<'s>(attributes: &[Attribute<'s>]) -> Option<&'s str> {
+    attribute_value(attributes, "lang").map(|value| value.text)
+}
+
+/// Attributes whose value is a JavaScript expression rather than text.
+fn is_directive(name: &str) -> bool {
+    name.starts_with(':')
+        || name.starts_with('@')
+        || name.starts_with('#')
+        || name.starts_with('.')
+        || name.starts_with("v-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::scan_vue;
+    use crate::scanner::file::{covered, scan_test_source};
+    use crate::scanner::FindingKind;
+    use oxc::allocator::Allocator;
+
+    #[test]
+    fn template_script_and_bindings() {
+        let source = "<template>\n  <div title=\"提示\" :label=\"'绑定'\">\n    你好 {{ name }} 再见 {{ ok ? '是' : '否' }}\n    <!-- 注释 -->\n  </div>\n</template>\n<script setup lang=\"ts\">\nconst msg: string = \"脚本\";\n</script>\n<style>\n.a { content: \"样式\"; }\n</style>\n";
+        let allocator = Allocator::default();
+        let results = scan_test_source(source, |ctx| scan_vue(ctx, &allocator));
+        let found: Vec<_> = results
+            .iter()
+            .map(|result| (covered(source, result), result.kind))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("提示", FindingKind::MarkupAttribute),
+                ("绑定", FindingKind::StringLiteral),
+                ("你好", FindingKind::MarkupText),
+                ("再见", FindingKind::MarkupText),
+                ("是", FindingKind::StringLiteral),
+                ("否", FindingKind::StringLiteral),
+                ("脚本", FindingKind::StringLiteral),
+            ]
+        );
+    }
+}
@@ -0,0 +1,352 @@
+//! YAML resource files, as used by Rails, Symfony and vue-i18n.
+//!
+//! This is a line-based reader for the subset such files use: nested block
+//! mappings and sequences, plain and quoted scalars and `|`/`>` block scalars.
+//! Flow collections (`[a, b]`, `{a: b}`) are checked as text under their key.
+//! Keys, comments, anchors and aliases are not reported.
+
+use super::file::{FileContext, FileScan};
+use super::key_path::KeyPath;
+
+pub(crate) fn scan_yaml(ctx: &FileContext) -> FileScan {
+    let source = ctx.source_text;
+    let mut out = FileScan::default();
+    let mut reader = YamlReader {
+        ctx,
+        out: &mut out,
+        path: KeyPath::default(),
+        levels: Vec::new(),
+        block_scalar: None,
+    };
+    let mut line_start = if source.starts_with('\u{feff}') { 3 } else { 0 };
+    while line_start < source.len() {
+        let line_end = source[line_start..]
+            .find('\n')
+            .map_or(source.len(), |p| line_start + p);
+        reader.line(
+            source[line_start..line_end].trim_end_matches('\r'),
+            line_start,
+        );
+        line_start = line_end + 1;
+    }
+    reader.end_block_scalar();
+    out
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Key,
+    Item(usize),
+}
+
+/// An open mapping entry or sequence item.
+struct Level {
+    indent: usize,
+    /// Length of the key path before this level's segment was pushed.
+    path_len: usize,
+    kind: Kind,
+}
+
+struct YamlReader<'c, 'o> {
+    ctx: &'c FileContext<'c>,
+    out: &'o mut FileScan,
+    path: KeyPath,
+    levels: Vec<Level>,
+    /// The `|` or `>` block scalar being read.
+    block_scalar: Option<BlockScalar>,
+}
+
+/// The lines of a block scalar, reported as one value once it ends.
+struct BlockScalar {
+    /// Indentation of the key or dash owning it; its lines are indented past
+    /// this.
+    owner_indent: usize,
+    /// `>` folds lines into one, `|` keeps them.
+    folded: bool,
+    /// Each line's indentation and content, blank lines included.
+    lines: Vec<(usize, usize, usize)>,
+}
+
+impl YamlReader<'_, '_> {
+    fn line(&mut self, line: &str, start: usize) {
+        let content = line.trim_start_matches(' ');
+        let indent = line.len() - content.len();
+        let content_start = start + indent;
+
+        if let Some(block) = &mut self.block_scalar {
+            if content.trim().is_empty() || indent > block.owner_indent {
+                let content = content.trim_end();
+                block
+                    .lines
+                    .push((indent, content_start, content_start + content.len()));
+                return;
+            }
+            self.end_block_scalar();
+        }
+
+        let content = content.trim_end();
+        if content.is_empty() || content.starts_with('#') || content.starts_with('%') {
+            return;
+        }
+        if indent == 0 && (content.starts_with("---") || content.starts_with("...")) {
+            // A new document.
+            self.levels.clear();
+            self.path.truncate(0);
+            return;
+        }
+        self.node(content, indent, content_start);
+    }
+
+    /// Reads a mapping entry, a sequence item or a bare scalar at `indent`.
+    fn node(&mut self, content: &str, indent: usize, start: usize) {
+        let is_item = content == "-" || content.starts_with("- ");
+        let mut next_index = 0;
+        while let Some(top) = self.levels.last() {
+            // Sequence items may sit at the same indentation as their key.
+            let parent =
+                top.indent < indent || (is_item && top.kind == Kind::Key && top.indent == indent);
+            if parent {
+                break;
+            }
+            if let (true, Kind::Item(index)) = (top.indent == indent, top.kind) {
+                next_index = index + 1;
+            }
+            self.path.truncate(top.path_len);
+            self.levels.pop();
+        }
+
+        if is_item {
+            self.push(indent, Kind::Item(next_index));
+            self.path.push_index(next_index);
+            let rest = &content[1..];
+            let lead = rest.len() - rest.trim_start().len();
+            if !rest.trim_start().is_empty() {
+                // `- key: value` opens a mapping inside the item.
+                self.node(rest.trim_start(), indent + 1 + lead, start + 1 + lead);
+            }
+            return;
+        }
+
+        if let Some((key, value_at)) = split_key(content) {
+            self.push(indent, Kind::Key);
+            self.path.push_key(key);
+            let value = &content[value_at..];
+            let lead = value.len() - value.trim_start().len();
+            self.value(value.trim_start(), start + value_at + lead);
+            return;
+        }
+
+        // A multi-line plain scalar or a flow collection spanning lines.
+        self.value(content, start);
+    }
+
+    /// Opens a level; the caller pushes its key path segment.
+    fn push(&mut self, indent: usize, kind: Kind) {
+        let path_len = self.path.len();
+        self.levels.push(Level {
+            indent,
+            path_len,
+            kind,
+        });
+    }
+
+    /// Reads the scalar after a key or dash.
+    fn value(&mut self, value: &str, start: usize) {
+        // Anchors and tags come before the value: `&default`, `!!str`.
+        let mut value = value;
+        let mut start = start;
+        while value.starts_with(['&', '!']) {
+            let end = value.find(' ').unwrap_or(value.len());
+            let rest = value[end..].trim_start();
+            start += value.len() - rest.len();
+            value = rest;
+        }
+
+        match value.as_bytes().first() {
+            None | Some(b'*' | b'#') => {}
+            Some(b'|' | b'>') => {
+                self.block_scalar = Some(BlockScalar {
+                    owner_indent: self.levels.last().map_or(0, |level| level.indent),
+                    folded: value.starts_with('>'),
+                    lines: Vec::new(),
+                });
+            }
+            Some(b'"') => {
+                let (decoded, raw) = double_quoted(&value[1..]);
+                self.check_decoded(&decoded, raw, start + 1);
+            }
+            Some(b'\'') => {
+                let raw = &value[1..];
+                let end = single_quote_end(raw);
+                let decoded = raw[..end].replace("''", "'");
+                self.check_decoded(&decoded, &raw[..end], start + 1);
+            }
+            Some(_) => {
+                let end = value.find(" #").unwrap_or(value.len());
+                self.check(value[..end].trim_end(), start);
+            }
+        }
+    }
+
+    /// Reports the block scalar being read, if any, as one value: its lines
+    /// without their common indentation, joined as `|` or `>` does.
+    fn end_block_scalar(&mut self) {
+        let Some(block) = self.block_scalar.take() else {
+            return;
+        };
+        let source = self.ctx.source_text;
+        let lines: Vec<_> = block
+            .lines
+            .iter()
+            .map(|&(indent, start, end)| (indent, &source[start..end]))
+            .collect();
+        let Some(first) = lines.iter().position(|(_, text)| !text.is_empty()) else {
+            return;
+        };
+        let last = lines
+            .iter()
+            .rposition(|(_, text)| !text.is_empty())
+            .unwrap_or(first);
+        // Lines indented past the first keep the extra spaces.
+        let base = lines[first].0;
+        let mut value = String::new();
+        for (i, &(indent, text)) in lines[first..=last].iter().enumerate() {
+            if i > 0 {
+                let previous_blank = lines[first + i - 1].1.is_empty();
+                // Folding turns a line break into a space, and a blank line
+                // into the only break.
+                match (block.folded, text.is_empty(), previous_blank) {
+                    (false, _, _) | (true, true, _) => value.push('\n'),
+                    (true, false, false) => value.push(' '),
+                    (true, false, true) => {}
+                }
+            }
+            if !text.is_empty() {
+                value.extend(std::iter::repeat(' ').take(indent.saturating_sub(base)));
+                value.push_str(text);
+            }
+        }
+        let (start, end) = (block.lines[first].1, block.lines[last].2);
+        self.check_decoded(&value, &source[start..end], start);
+    }
+
+    fn check(&mut self, text: &str, start: usize) {
+        self.check_decoded(text, text, start);
+    }
+
+    fn check_decoded(&mut self, value: &str, raw: &str, start: usize) {
+        if self.ctx.chinese_regex.is_match(value) {
+            let key = self.path.to_string();
+            self.ctx.check_value(value, raw, start, &key, self.out);
+        }
+    }
+}
+
+/// Splits `key: value`, returning the unquoted key and where the value starts.
+fn split_key(content: &str) -> Option<(String, usize)> {
+    let bytes = content.as_bytes();
+    let (key, after_key) = match bytes.first()? {
+        quote @ (b'"' | b'\'') => {
+            let end = content[1..].find(*quote as char)? + 1;
+            (content[1..end].to_string(), end + 1)
+        }
+        b'[' | b'{' | b'?' | b'|' | b'>' | b'&' | b'*' | b'!' => return None,
+        _ => {
+            let colon = (0..bytes.len()).find(|&i| {
+                bytes[i] == b':' && bytes.get(i + 1).map_or(true, |b| *b == b' ' || *b == b'\t')
+            })?;
+            return Some((content[..colon].trim_end().to_string(), colon + 1));
+        }
+    };
+    let rest = &content[after_key..];
+    let colon = after_key + rest.len() - rest.trim_start().len();
+    (bytes.get(colon) == Some(&b':')).then_some((key, colon + 1))
+}
+
+/// Decodes a double-quoted scalar; `raw` starts after the opening quote.
+/// Returns the value and its raw text up to the closing quote or line end.
+fn double_quoted(raw: &str) -> (String, &str) {
+    let mut value = String::new();
+    let mut chars = raw.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return (value, &raw[..i]),
+            '\\' => {
+                let Some((_, escape)) = chars.next() else {
+                    break;
+                };
+                let hex_len = match escape {
+                    'x' => 2,
+                    'u' => 4,
+                    'U' => 8,
+                    _ => 0,
+                };
+                if hex_len > 0 {
+                    let hex: String = chars.by_ref().take(hex_len).map(|(_, c)| c).collect();
+                    let decoded = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32);
+                    value.push(decoded.unwrap_or('\u{fffd}'));
+                    continue;
+                }
+                value.push(match escape {
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    '0' => '\0',
+                    other => other,
+                });
+            }
+            c => value.push(c),
+        }
+    }
+    (value, raw)
+}
+
+/// The offset of the closing quote of a single-quoted scalar, where `''` is
+/// an escaped quote, or the line end.
+fn single_quote_end(raw: &str) -> usize {
+    let bytes = raw.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'\'' {
+            if bytes.get(i + 1) == Some(&b'\'') {
+                i += 2;
+                continue;
+            }
+            return i;
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::scan_yaml;
+    use crate::scanner::file::{covered, keyed, scan_test_source};
+
+    #[test]
+    fn values_under_key_paths() {
+        let source = "common:\n  save: 保存\n  quoted: \"取\\u6d88\"\n  list:\n    - 第一\n  multi: |\n    多行\n    文本\n  next: 下一个\n";
+        let results = scan_test_source(source, scan_yaml);
+        assert_eq!(results.len(), 5);
+        assert_eq!(covered(source, keyed(&results, "common.save")), "保存");
+        let quoted = keyed(&results, "common.quoted");
+        assert_eq!(quoted.text, "取消");
+        assert_eq!(covered(source, quoted), "取\\u6d88");
+        assert_eq!(covered(source, keyed(&results, "common.list[0]")), "第一");
+        let multi = keyed(&results, "common.multi");
+        assert_eq!(multi.text, "多行\n文本");
+        assert_eq!(covered(source, multi), "多行\n    文本");
+        assert_eq!(covered(source, keyed(&results, "common.next")), "下一个");
+    }
+
+    #[test]
+    fn folded_block_scalar_is_one_value() {
+        let source = "a: >\n  折叠\n  文本\n\n  第二段\n";
+        let results = scan_test_source(source, scan_yaml);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].text, "折叠 文本\n第二段");
+        assert_eq!(covered(source, &results[0]), "折叠\n  文本\n\n  第二段");
+        assert_eq!(results[0].matches.len(), 3);
+    }
+}
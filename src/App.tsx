@@ -1,40 +1,232 @@
-import { useState, useMemo } from 'react'
+import { useState, useMemo, useRef, type ReactNode } from 'react'
 import { open } from '@tauri-apps/plugin-dialog'
 import { invoke } from '@tauri-apps/api/core'
-import { FolderOpen, Play, ChevronRight, Expand, Minimize, FileText, Info, Settings2 } from 'lucide-react'
+import { listen } from '@tauri-apps/api/event'
+import {
+  FolderOpen,
+  Play,
+  Square,
+  ChevronRight,
+  Expand,
+  Minimize,
+  FileText,
+  Info,
+  Settings2,
+  TriangleAlert,
+  ListChecks,
+  X,
+} from 'lucide-react'
 import { Button } from '@/components/ui/button'
 import { Input } from '@/components/ui/input'
 import { Label } from '@/components/ui/label'
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
 import { Tooltip, TooltipContent, TooltipTrigger } from '@/components/ui/tooltip'
 
+type FindingKind =
+  | 'stringLiteral'
+  | 'templateLiteral'
+  | 'jsxText'
+  | 'jsxAttribute'
+  | 'markupText'
+  | 'markupAttribute'
+  | 'resource'
+  | 'comment'
+  | 'identifier'
+  | 'propertyKey'
+  | 'importSpecifier'
+  | 'filePath'
+
+interface FindingContext {
+  function?: string
+  callee?: string
+  property?: string
+}
+
+const KIND_LABELS: Partial<Record<FindingKind, string>> = {
+  comment: '注释',
+  identifier: '标识符',
+  propertyKey: '属性名',
+  importSpecifier: '导入',
+  filePath: '文件路径',
+}
+
+type Script = 'han' | 'hiragana' | 'katakana' | 'hangul' | 'non-ascii' | 'custom'
+
+const SCRIPT_LABELS: Record<Script, string> = {
+  han: '汉字',
+  hiragana: '平假名',
+  katakana: '片假名',
+  hangul: '韩文',
+  'non-ascii': '非 ASCII',
+  custom: '自定义',
+}
+
+interface MatchRange {
+  text: string
+  line: number
+  column: number
+  endLine: number
+  endColumn: number
+  startOffset: number
+  endOffset: number
+}
+
 interface ScanResult {
   filePath: string
   line: number
   column: number
+  endLine: number
+  endColumn: number
+  startOffset: number
+  endOffset: number
   text: string
+  matches: MatchRange[]
+  scripts: Script[]
+  confidence: 'high' | 'low'
+  kind: FindingKind
+  context?: FindingContext
+  key?: string
+  locale?: string
+  config?: string
+}
+
+// Marks each matched run in the finding's text, looked up in order.
+function highlightMatches(text: string, matches: MatchRange[]) {
+  const parts: ReactNode[] = []
+  let from = 0
+  for (const match of matches) {
+    const index = text.indexOf(match.text, from)
+    if (index < 0) continue
+    parts.push(text.slice(from, index))
+    parts.push(
+      <mark key={index} className="bg-amber-500/20 text-inherit rounded-sm">
+        {match.text}
+      </mark>,
+    )
+    from = index + match.text.length
+  }
+  parts.push(text.slice(from))
+  return parts
+}
+
+interface ScanDiagnostic {
+  filePath: string
+  kind: 'walk' | 'read' | 'parse' | 'config'
+  message: string
+  line: number | null
+  column: number | null
+}
+
+interface ScanReport {
+  results: ScanResult[]
+  scriptCounts: Partial<Record<Script, number>>
+  configs: string[]
+  filesScanned: number
+  diagnostics: ScanDiagnostic[]
+  cancelled: boolean
+}
+
+type SkipReason =
+  | 'excluded'
+  | 'ignored'
+  | 'versionControl'
+  | 'hidden'
+  | 'tooDeep'
+  | 'symlink'
+  | 'notAFile'
+  | 'notIncluded'
+  | 'declaration'
+  | 'unknownType'
+  | 'chineseLocale'
+  | 'tooLarge'
+
+const SKIP_LABELS: Record<SkipReason, string> = {
+  excluded: '匹配排除规则',
+  ignored: '被 .gitignore/.ignore/.chinesescanignore 忽略',
+  versionControl: '版本控制目录',
+  hidden: '隐藏文件',
+  tooDeep: '超过最大深度',
+  symlink: '符号链接，未跟随',
+  notAFile: '不是普通文件',
+  notIncluded: '不匹配包含规则',
+  declaration: '声明文件',
+  unknownType: '不支持的文件类型',
+  chineseLocale: '中文语言文件',
+  tooLarge: '超过最大文件大小',
+}
+
+interface PlannedFile {
+  filePath: string
+  isDir: boolean
+  fileType?: string
+  locale?: string
+  skipped?: SkipReason
+  config?: string
+}
+
+interface DryRunReport {
+  files: PlannedFile[]
+  configs: string[]
+  diagnostics: ScanDiagnostic[]
+}
+
+interface ScanProgress {
+  scanId: string
+  filesDiscovered: number
+  filesScanned: number
+  findings: number
+  currentFile: string
+}
+
+interface FileResults {
+  scanId: string
+  filePath: string
+  results: ScanResult[]
 }
 
 type GroupedResults = Record<string, ScanResult[]>
 
 function App() {
   const [scanPath, setScanPath] = useState('')
+  const [includePatterns, setIncludePatterns] = useState('')
   const [excludePatterns, setExcludePatterns] = useState('')
+  const [gitignore, setGitignore] = useState(true)
+  const [skipHidden, setSkipHidden] = useState(false)
+  const [followLinks, setFollowLinks] = useState(false)
+  const [maxDepth, setMaxDepth] = useState('')
+  const [maxFileSize, setMaxFileSize] = useState('')
+  const [fileTypes, setFileTypes] = useState('')
+  const [skipDeclarations, setSkipDeclarations] = useState(false)
+  const [comments, setComments] = useState(false)
+  const [hideComments, setHideComments] = useState(false)
+  const [identifiers, setIdentifiers] = useState(false)
+  const [locales, setLocales] = useState('')
+  const [scripts, setScripts] = useState('')
+  const [pattern, setPattern] = useState('')
+  const [scriptCounts, setScriptCounts] = useState<ScanReport['scriptCounts']>({})
+  const [configs, setConfigs] = useState<string[]>([])
+  const [dryRun, setDryRun] = useState<DryRunReport | null>(null)
   const [results, setResults] = useState<ScanResult[]>([])
   const [isLoading, setIsLoading] = useState(false)
   const [error, setError] = useState<string | null>(null)
   const [expandedFiles, setExpandedFiles] = useState<Record<string, boolean>>({})
+  const [progress, setProgress] = useState<ScanProgress | null>(null)
+  const [cancelled, setCancelled] = useState(false)
+  const [diagnostics, setDiagnostics] = useState<ScanDiagnostic[]>([])
+  const [showDiagnostics, setShowDiagnostics] = useState(false)
+  const scanIdRef = useRef<string | null>(null)
 
   const groupedResults = useMemo(() => {
     return results.reduce((acc, result) => {
       const { filePath } = result
+      if (hideComments && result.kind === 'comment') return acc
       if (!acc[filePath]) {
         acc[filePath] = []
       }
       acc[filePath].push(result)
       return acc
     }, {} as GroupedResults)
-  }, [results])
+  }, [results, hideComments])
 
   const toggleFileExpansion = (filePath: string) => {
     setExpandedFiles(prev => ({ ...prev, [filePath]: !prev[filePath] }))
@@ -68,6 +260,38 @@ function App() {
     }
   }
 
+  // The settings the scan_directory and dry_run commands take.
+  const scanSettings = () => ({
+    path: scanPath,
+    include: includePatterns,
+    exclude: excludePatterns,
+    fileTypes,
+    skipDeclarations,
+    locales,
+    comments,
+    identifiers,
+    scripts,
+    pattern,
+    gitignore,
+    skipHidden,
+    followLinks,
+    maxDepth: maxDepth.trim() ? Number(maxDepth) : null,
+    maxFileSize,
+  })
+
+  const previewFiles = async () => {
+    if (!scanPath) {
+      setError('Please select a directory to scan.')
+      return
+    }
+    setError(null)
+    try {
+      setDryRun(await invoke<DryRunReport>('dry_run', { settings: scanSettings() }))
+    } catch (err: any) {
+      setError(`An error occurred during dry run: ${err.toString()}`)
+    }
+  }
+
   const startScan = async () => {
     if (!scanPath) {
       setError('Please select a directory to scan.')
@@ -77,22 +301,55 @@ function App() {
     setError(null)
     setResults([])
     setExpandedFiles({})
+    setProgress(null)
+    setCancelled(false)
+    setDiagnostics([])
+    setShowDiagnostics(false)
+    setScriptCounts({})
+    setConfigs([])
+    setDryRun(null)
+
+    const scanId = crypto.randomUUID()
+    scanIdRef.current = scanId
+
+    // Findings stream in per file while the scan runs; the final sorted list replaces them.
+    const unlistenProgress = await listen<ScanProgress>('scan-progress', event => {
+      if (event.payload.scanId === scanId) setProgress(event.payload)
+    })
+    const unlistenResults = await listen<FileResults>('scan-results', event => {
+      if (event.payload.scanId === scanId) setResults(prev => [...prev, ...event.payload.results])
+    })
 
     try {
-      const res = await invoke<ScanResult[]>('scan_directory', {
-        path: scanPath,
-        exclude: excludePatterns,
-      })
-      setResults(res)
+      const report = await invoke<ScanReport>('scan_directory', { scanId, settings: scanSettings() })
+      setResults(report.results)
+      setScriptCounts(report.scriptCounts)
+      setConfigs(report.configs)
+      setCancelled(report.cancelled)
+      setDiagnostics(report.diagnostics)
       // Automatically expand all files by default after scan
       // expandAll()
     } catch (err: any) {
       setError(`An error occurred during scan: ${err.toString()}`)
     } finally {
+      unlistenProgress()
+      unlistenResults()
+      scanIdRef.current = null
+      setProgress(null)
       setIsLoading(false)
     }
   }
 
+  const cancelScan = async () => {
+    const scanId = scanIdRef.current
+    if (!scanId) return
+    try {
+      await invoke('cancel_scan', { scanId })
+    } catch (err: any) {
+      setError(`Failed to cancel scan: ${err.toString()}`)
+    }
+  }
+
   return (
     <div className="min-h-screen flex flex-col items-center p-4">
       <div className="w-full max-w-4xl space-y-8">
@@ -113,10 +370,23 @@ function App() {
                 </CardTitle>
                 <CardDescription>选择要扫描的代码目录和排除规则</CardDescription>
               </div>
-              <Button onClick={startScan} disabled={isLoading}>
-                <Play />
-                {isLoading ? '扫描中...' : '开始扫描'}
-              </Button>
+              {isLoading ? (
+                <Button onClick={cancelScan} variant="outline">
+                  <Square />
+                  停止扫描
+                </Button>
+              ) : (
+                <div className="flex gap-2">
+                  <Button onClick={previewFiles} variant="outline">
+                    <ListChecks />
+                    预览文件
+                  </Button>
+                  <Button onClick={startScan}>
+                    <Play />
+                    开始扫描
+                  </Button>
+                </div>
+              )}
             </div>
           </CardHeader>
           <CardContent className="space-y-6">
@@ -128,7 +398,7 @@ function App() {
                     <Info className="w-4 h-4" />
                   </TooltipTrigger>
                   <TooltipContent>
-                    <p>只匹配这些文件：/\*.[jt]s(x)?/</p>
+                    <p>只匹配这些文件：/\*.([mc]?[jt]s|[jt]sx|vue|svelte|astro|[wa]xml|wxs|sjs|html?|ejs|hbs|handlebars|pug|jade|properties|po|strings|stringsdict|xcstrings|arb|java|go|pyi?|kts?)/、Android values*/ 下的 XML、小程序配置 JSON、非中文语言的 JSON/YAML 语言文件及带 node、python 等 #! 行的无扩展名脚本</p>
                   </TooltipContent>
                 </Tooltip>
               </Label>
@@ -144,6 +414,26 @@ function App() {
                 </Button>
               </div>
             </div>
+            <div className="flex gap-2 items-center">
+              <Label className="w-20 shrink-0 flex items-center gap-2" htmlFor="includePatterns">
+                Include
+                <Tooltip>
+                  <TooltipTrigger>
+                    <Info className="w-4 h-4" />
+                  </TooltipTrigger>
+                  <TooltipContent>
+                    <p>
+                      1. 只扫描匹配的文件，留空则不限
+                      <br />
+                      2. glob 格式，逗号（,）分割，如 src/pages/**
+                      <br />
+                      3. Exclude 与 .gitignore 优先：被它们排除的文件即使匹配也不扫描
+                    </p>
+                  </TooltipContent>
+                </Tooltip>
+              </Label>
+              <Input id="includePatterns" value={includePatterns} onChange={e => setIncludePatterns(e.target.value)} />
+            </div>
             <div className="flex gap-2 items-center">
               <Label className="w-20 shrink-0 flex items-center gap-2" htmlFor="excludePatterns">
                 Exclude
@@ -153,7 +443,7 @@ function App() {
                   </TooltipTrigger>
                   <TooltipContent>
                     <p>
-                      1. 默认忽略.gitignore
+                      1. 默认忽略 .gitignore、.ignore 与 .chinesescanignore
                       <br />
                       2. glob 格式，逗号（,）分割
                     </p>
@@ -162,6 +452,130 @@ function App() {
               </Label>
               <Input id="excludePatterns" value={excludePatterns} onChange={e => setExcludePatterns(e.target.value)} />
             </div>
+            <div className="flex gap-2 items-center">
+              <Label className="w-20 shrink-0 flex items-center gap-2" htmlFor="maxDepth">
+                遍历
+                <Tooltip>
+                  <TooltipTrigger>
+                    <Info className="w-4 h-4" />
+                  </TooltipTrigger>
+                  <TooltipContent>
+                    <p>
+                      1. 最大深度：1 只扫描所选目录下的文件，留空不限
+                      <br />
+                      2. 最大文件：超过的文件跳过，如 500K、1M，留空不限
+                      <br />
+                      3. .chinesescanignore 写法同 .gitignore，始终生效
+                    </p>
+                  </TooltipContent>
+                </Tooltip>
+              </Label>
+              <Input
+                id="maxDepth"
+                type="number"
+                min={1}
+                placeholder="最大深度"
+                value={maxDepth}
+                onChange={e => setMaxDepth(e.target.value)}
+              />
+              <Input placeholder="最大文件，如 1M" value={maxFileSize} onChange={e => setMaxFileSize(e.target.value)} />
+              <label className="shrink-0 flex items-center gap-1 text-sm">
+                <input type="checkbox" checked={gitignore} onChange={e => setGitignore(e.target.checked)} />
+                遵循 .gitignore
+              </label>
+              <label className="shrink-0 flex items-center gap-1 text-sm">
+                <input type="checkbox" checked={skipHidden} onChange={e => setSkipHidden(e.target.checked)} />
+                跳过隐藏文件
+              </label>
+              <label className="shrink-0 flex items-center gap-1 text-sm">
+                <input type="checkbox" checked={followLinks} onChange={e => setFollowLinks(e.target.checked)} />
+                跟随符号链接
+              </label>
+            </div>
+            <div className="flex gap-2 items-center">
+              <Label className="w-20 shrink-0 flex items-center gap-2" htmlFor="fileTypes">
+                文件类型
+                <Tooltip>
+                  <TooltipTrigger>
+                    <Info className="w-4 h-4" />
+                  </TooltipTrigger>
+                  <TooltipContent>
+                    <p>
+                      1. 格式为 模式=类型，逗号（,）分割，如 .es6=js, legacy/**/*.js=cjs
+                      <br />
+                      2. 模式为 glob 或 .扩展名，后写的规则优先
+                      <br />
+                      3. 类型：js mjs cjs jsx ts tsx dts vue svelte astro wxml miniprogram-json html ejs hbs pug json
+                      yaml properties po android strings stringsdict xcstrings arb java go py kt
+                    </p>
+                  </TooltipContent>
+                </Tooltip>
+              </Label>
+              <Input id="fileTypes" value={fileTypes} onChange={e => setFileTypes(e.target.value)} />
+              <label className="shrink-0 flex items-center gap-1 text-sm">
+                <input
+                  type="checkbox"
+                  checked={skipDeclarations}
+                  onChange={e => setSkipDeclarations(e.target.checked)}
+                />
+                跳过 .d.ts
+              </label>
+              <label className="shrink-0 flex items-center gap-1 text-sm">
+                <input type="checkbox" checked={comments} onChange={e => setComments(e.target.checked)} />
+                扫描注释
+              </label>
+              <label className="shrink-0 flex items-center gap-1 text-sm">
+                <input type="checkbox" checked={identifiers} onChange={e => setIdentifiers(e.target.checked)} />
+                扫描命名
+              </label>
+            </div>
+            <div className="flex gap-2 items-center">
+              <Label className="w-20 shrink-0 flex items-center gap-2" htmlFor="locales">
+                语言文件
+                <Tooltip>
+                  <TooltipTrigger>
+                    <Info className="w-4 h-4" />
+                  </TooltipTrigger>
+                  <TooltipContent>
+                    <p>
+                      1. 格式为 glob=语言，逗号（,）分割，如 i18n/*.json=en
+                      <br />
+                      2. 未指定时按路径识别，如 en.json、locales/en/common.json、values-en、en.lproj
+                      <br />
+                      3. zh 开头的语言（zh-CN、zh_TW 等）的文件不扫描
+                    </p>
+                  </TooltipContent>
+                </Tooltip>
+              </Label>
+              <Input id="locales" value={locales} onChange={e => setLocales(e.target.value)} />
+            </div>
+            <div className="flex gap-2 items-center">
+              <Label className="w-20 shrink-0 flex items-center gap-2" htmlFor="scripts">
+                文字
+                <Tooltip>
+                  <TooltipTrigger>
+                    <Info className="w-4 h-4" />
+                  </TooltipTrigger>
+                  <TooltipContent>
+                    <p>
+                      1. 要查找的文字，逗号（,）分割，默认 han
+                      <br />
+                      2. 可选：han hiragana katakana hangul non-ascii
+                      <br />
+                      3. 右侧可填写自定义正则；只填正则时仅查找正则的匹配
+                    </p>
+                  </TooltipContent>
+                </Tooltip>
+              </Label>
+              <Input id="scripts" value={scripts} placeholder="han" onChange={e => setScripts(e.target.value)} />
+              <Input
+                id="pattern"
+                value={pattern}
+                placeholder="自定义正则"
+                className="font-mono"
+                onChange={e => setPattern(e.target.value)}
+              />
+            </div>
 
             {error && (
               <div className="bg-destructive/10 border border-destructive/20 text-destructive p-4 rounded-md">
@@ -179,12 +593,37 @@ function App() {
               <FileText />
               扫描结果
             </CardTitle>
-            {results.length > 0 && (
-              <CardDescription className="mt-1">共找到 {results.length} 处中文内容</CardDescription>
+            {isLoading && progress ? (
+              <CardDescription className="mt-1 truncate max-w-xl">
+                已扫描 {progress.filesScanned} 个文件，发现 {progress.findings} 处 ·{' '}
+                <span className="font-mono">{progress.currentFile}</span>
+              </CardDescription>
+            ) : (
+              results.length > 0 && (
+                <CardDescription className="mt-1">
+                  共找到 {results.length} 处中文内容
+                  {Object.keys(scriptCounts).length > 1 &&
+                    `（${Object.entries(scriptCounts)
+                      .map(([script, count]) => `${SCRIPT_LABELS[script as Script]} ${count}`)
+                      .join('，')}）`}
+                  {cancelled && '（扫描已中止，结果不完整）'}
+                  {configs.length > 0 && (
+                    <span className="block text-xs">
+                      已应用配置：<span className="font-mono">{configs.join('、')}</span>
+                    </span>
+                  )}
+                </CardDescription>
+              )
             )}
           </div>
           {results.length > 0 && (
             <div className="flex space-x-2">
+              {results.some(result => result.kind === 'comment') && (
+                <label className="flex items-center gap-1 text-sm">
+                  <input type="checkbox" checked={hideComments} onChange={e => setHideComments(e.target.checked)} />
+                  隐藏注释
+                </label>
+              )}
               <Button onClick={expandAll} variant="outline" size="sm">
                 <Expand />
                 全部展开
@@ -196,6 +635,57 @@ function App() {
             </div>
           )}
         </div>
+        {dryRun && (
+          <div className="bg-card border rounded-md p-4 text-sm">
+            <div className="flex items-center justify-between">
+              <span className="font-semibold flex items-center gap-2">
+                <ListChecks className="h-4 w-4" />
+                将扫描 {dryRun.files.filter(file => !file.skipped).length} 个文件，跳过{' '}
+                {dryRun.files.filter(file => file.skipped).length} 项
+              </span>
+              <Button onClick={() => setDryRun(null)} variant="ghost" size="sm">
+                <X />
+              </Button>
+            </div>
+            {dryRun.configs.length > 0 && (
+              <p className="mt-1 text-xs text-muted-foreground">
+                已应用配置：<span className="font-mono">{dryRun.configs.join('、')}</span>
+              </p>
+            )}
+            <ul className="mt-2 max-h-80 overflow-auto space-y-0.5 font-mono">
+              {dryRun.files.map(file => (
+                <li key={file.filePath} className={file.skipped ? 'text-muted-foreground' : ''}>
+                  {file.filePath}
+                  {file.isDir && '/'}
+                  <span className="ml-2 text-xs font-sans">
+                    {file.skipped
+                      ? `跳过：${SKIP_LABELS[file.skipped]}`
+                      : `${file.fileType}${file.locale ? ` · ${file.locale}` : ''}`}
+                  </span>
+                </li>
+              ))}
+            </ul>
+          </div>
+        )}
+        {diagnostics.length > 0 && (
+          <div className="bg-amber-500/10 border border-amber-500/20 text-amber-700 p-4 rounded-md text-sm">
+            <button className="flex items-center gap-2 font-semibold" onClick={() => setShowDiagnostics(v => !v)}>
+              <ChevronRight className={`h-4 w-4 transition-transform ${showDiagnostics ? 'rotate-90' : ''}`} />
+              <TriangleAlert className="h-4 w-4" />
+              {new Set(diagnostics.map(d => d.filePath)).size} 个文件未能完整扫描，其中的中文内容可能遗漏
+            </button>
+            {showDiagnostics && (
+              <ul className="mt-2 space-y-1 font-mono">
+                {diagnostics.map((d, index) => (
+                  <li key={index}>
+                    {d.filePath}
+                    {d.line !== null && `:${d.line}:${d.column}`} — {d.message}
+                  </li>
+                ))}
+              </ul>
+            )}
+          </div>
+        )}
         <div className="bg-card border rounded-md">
           {Object.keys(groupedResults).length > 0 ? (
             <div>
@@ -232,7 +722,41 @@ function App() {
                               <tr key={index} className="">
                                 <td className="p-2 font-mono text-muted-foreground">{item.line}</td>
                                 <td className="p-2 font-mono text-muted-foreground">{item.column}</td>
-                                <td className="p-2 font-mono text-muted-foreground">{item.text}</td>
+                                <td className="p-2 font-mono text-muted-foreground">
+                                  {item.key && (
+                                    <span className="mr-2 text-xs text-primary">
+                                      {item.locale && `[${item.locale}] `}
+                                      {item.key}
+                                    </span>
+                                  )}
+                                  {KIND_LABELS[item.kind] && (
+                                    <span className="mr-2 text-xs font-sans text-muted-foreground bg-muted px-1.5 py-0.5 rounded">
+                                      {KIND_LABELS[item.kind]}
+                                    </span>
+                                  )}
+                                  {highlightMatches(item.text, item.matches)}
+                                  {item.context && (
+                                    <span className="ml-2 text-xs font-sans text-muted-foreground">
+                                      {[
+                                        item.context.function && `${item.context.function}()`,
+                                        item.context.callee && `→ ${item.context.callee}()`,
+                                        item.context.property && `${item.context.property}=`,
+                                      ]
+                                        .filter(Boolean)
+                                        .join(' ')}
+                                    </span>
+                                  )}
+                                  {item.confidence === 'low' && (
+                                    <Tooltip>
+                                      <TooltipTrigger className="ml-2 text-xs font-sans text-amber-700 bg-amber-500/10 px-1.5 py-0.5 rounded">
+                                        低置信度
+                                      </TooltipTrigger>
+                                      <TooltipContent>
+                                        <p>文件无法解析，由词法级回退扫描得到，可能存在误报</p>
+                                      </TooltipContent>
+                                    </Tooltip>
+                                  )}
+                                </td>
                               </tr>
                             ))}
                           </tbody>